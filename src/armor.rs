@@ -0,0 +1,243 @@
+use super::keys::{PublicKey, RelinearizationKey1, RelinearizationKey2, SecretKey};
+use super::params::Parameters;
+use super::serialization::SerializationError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+// The armored format of a key is its binary format (see `serialization`) in base64, between
+// lines naming the key type, after a header with the hash of its parameters:
+//
+// -----BEGIN BFV PUBLIC KEY-----
+// Parameters: 6c1ed7c3e16a45b9
+//
+// QkZWAAEBAAQAAAAAAAA...
+// -----END BFV PUBLIC KEY-----
+const PUBLIC_KEY: &str = "PUBLIC KEY";
+const RELINEARIZATION_KEY_1: &str = "RELINEARIZATION KEY 1";
+const RELINEARIZATION_KEY_2: &str = "RELINEARIZATION KEY 2";
+const SECRET_KEY: &str = "SECRET KEY";
+
+const PARAMETERS_HEADER: &str = "Parameters: ";
+const LINE_LENGTH: usize = 64;
+
+impl PublicKey {
+    /// Export the key as base64-armored text, for debugging or storage.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, PublicKey, SecretKey};
+    ///
+    /// let params = Parameters::new(16, 65536, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    ///
+    /// let text = public_key.to_armored();
+    /// assert!(text.starts_with("-----BEGIN BFV PUBLIC KEY-----\n"));
+    /// let loaded = PublicKey::from_armored(&text).unwrap();
+    /// assert_eq!(loaded.to_bytes(), public_key.to_bytes());
+    /// ```
+    pub fn to_armored(&self) -> String {
+        armor(PUBLIC_KEY, &self.params, &self.to_bytes())
+    }
+
+    /// Load a key exported with `to_armored`, checking the key type and the parameter hash.
+    pub fn from_armored(text: &str) -> Result<PublicKey, SerializationError> {
+        let (hash, bytes) = dearmor(PUBLIC_KEY, text)?;
+        let key = PublicKey::from_bytes(&bytes)?;
+        check_hash(hash, &key.params)?;
+        Ok(key)
+    }
+}
+
+impl RelinearizationKey1 {
+    /// Export the key as base64-armored text, for debugging or storage.
+    pub fn to_armored(&self) -> String {
+        armor(RELINEARIZATION_KEY_1, &self.params, &self.to_bytes())
+    }
+
+    /// Load a key exported with `to_armored`, checking the key type and the parameter hash.
+    pub fn from_armored(text: &str) -> Result<RelinearizationKey1, SerializationError> {
+        let (hash, bytes) = dearmor(RELINEARIZATION_KEY_1, text)?;
+        let key = RelinearizationKey1::from_bytes(&bytes)?;
+        check_hash(hash, &key.params)?;
+        Ok(key)
+    }
+}
+
+impl RelinearizationKey2 {
+    /// Export the key as base64-armored text, for debugging or storage.
+    pub fn to_armored(&self) -> String {
+        armor(RELINEARIZATION_KEY_2, &self.params, &self.to_bytes())
+    }
+
+    /// Load a key exported with `to_armored`, checking the key type and the parameter hash.
+    pub fn from_armored(text: &str) -> Result<RelinearizationKey2, SerializationError> {
+        let (hash, bytes) = dearmor(RELINEARIZATION_KEY_2, text)?;
+        let key = RelinearizationKey2::from_bytes(&bytes)?;
+        check_hash(hash, &key.params)?;
+        Ok(key)
+    }
+}
+
+impl SecretKey {
+    /// Export the secret key as base64-armored text, e.g. for key escrow.
+    /// The text is not encrypted: anyone who can read it can decrypt every ciphertext under
+    /// the key. The header holds the hash of `params`, since the key doesn't hold them.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(16, 65536, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    ///
+    /// let text = secret_key.to_armored_unencrypted(&params);
+    /// let loaded = SecretKey::from_armored(&text, &params).unwrap();
+    /// assert_eq!(loaded.to_bytes(), secret_key.to_bytes());
+    /// ```
+    pub fn to_armored_unencrypted(&self, params: &Parameters) -> String {
+        assert_eq!(self.poly.degree(), params.degree());
+        armor(SECRET_KEY, params, &self.to_bytes())
+    }
+
+    /// Load a secret key exported with `to_armored_unencrypted`, checking the key type, and
+    /// that the parameter hash and the degree match `params`.
+    pub fn from_armored(text: &str, params: &Parameters) -> Result<SecretKey, SerializationError> {
+        let (hash, bytes) = dearmor(SECRET_KEY, text)?;
+        check_hash(hash, params)?;
+        let key = SecretKey::from_bytes(&bytes)?;
+        if key.poly.degree() != params.degree() {
+            return Err(SerializationError::ParametersMismatch);
+        }
+        Ok(key)
+    }
+}
+
+fn armor(label: &str, params: &Parameters, bytes: &[u8]) -> String {
+    let mut text = format!("-----BEGIN BFV {}-----\n", label);
+    text += &format!("{}{:016x}\n\n", PARAMETERS_HEADER, params.id());
+    let encoded = STANDARD.encode(bytes);
+    for line in encoded.as_bytes().chunks(LINE_LENGTH) {
+        // The base64 alphabet is ASCII, so every chunk is valid UTF-8.
+        text += std::str::from_utf8(line).unwrap();
+        text.push('\n');
+    }
+    text += &format!("-----END BFV {}-----\n", label);
+    text
+}
+
+// Split armored text of the given type into the parameter hash and the decoded bytes.
+fn dearmor(label: &str, text: &str) -> Result<(u64, Vec<u8>), SerializationError> {
+    let mut lines = text.trim().lines().map(str::trim);
+    if lines.next() != Some(&format!("-----BEGIN BFV {}-----", label)) {
+        return Err(SerializationError::InvalidArmor);
+    }
+    let hash = lines
+        .next()
+        .and_then(|line| line.strip_prefix(PARAMETERS_HEADER))
+        .and_then(|hash| u64::from_str_radix(hash, 16).ok())
+        .ok_or(SerializationError::InvalidArmor)?;
+    if lines.next() != Some("") {
+        return Err(SerializationError::InvalidArmor);
+    }
+
+    let mut encoded = String::new();
+    let end = format!("-----END BFV {}-----", label);
+    loop {
+        match lines.next() {
+            Some(line) if line == end => break,
+            Some(line) => encoded += line,
+            None => return Err(SerializationError::InvalidArmor),
+        }
+    }
+    if lines.next().is_some() {
+        return Err(SerializationError::InvalidArmor);
+    }
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|_| SerializationError::InvalidArmor)?;
+    Ok((hash, bytes))
+}
+
+fn check_hash(hash: u64, params: &Parameters) -> Result<(), SerializationError> {
+    if hash == params.id() {
+        Ok(())
+    } else {
+        Err(SerializationError::ParametersMismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn armor_round_trip_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(33);
+        let params = Parameters::new(16, 65537, 12, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen_seeded(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+        let rlk_2 = secret_key.relin_key_gen_2(&params, params.q() * params.q(), &mut rng);
+
+        let text = public_key.to_armored();
+        assert!(text.lines().all(|line| line.len() <= LINE_LENGTH));
+        let loaded = PublicKey::from_armored(&text).unwrap();
+        assert_eq!(loaded.to_bytes(), public_key.to_bytes());
+        let loaded = RelinearizationKey1::from_armored(&rlk_1.to_armored()).unwrap();
+        assert_eq!(loaded.to_bytes(), rlk_1.to_bytes());
+        let loaded = RelinearizationKey2::from_armored(&rlk_2.to_armored()).unwrap();
+        assert_eq!(loaded.to_bytes(), rlk_2.to_bytes());
+
+        // Surrounding whitespace and CRLF line endings are accepted
+        let crlf = format!("\n  {}\n", text.replace('\n', "\r\n"));
+        assert!(PublicKey::from_armored(&crlf).is_ok());
+
+        let text = secret_key.to_armored_unencrypted(&params);
+        let loaded = SecretKey::from_armored(&text, &params).unwrap();
+        assert_eq!(loaded.poly, secret_key.poly);
+    }
+
+    #[test]
+    fn invalid_armor_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(34);
+        let params = Parameters::new(16, 65537, 12, 3.2).unwrap();
+        let other_params = Parameters::new(16, 65537, 16, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let text = secret_key.public_key_gen(&params, &mut rng).to_armored();
+
+        assert_eq!(
+            RelinearizationKey1::from_armored(&text).unwrap_err(),
+            SerializationError::InvalidArmor
+        );
+        let truncated = text.replace("-----END BFV PUBLIC KEY-----\n", "");
+        assert_eq!(
+            PublicKey::from_armored(&truncated).unwrap_err(),
+            SerializationError::InvalidArmor
+        );
+        let corrupted = text.replacen("QkZW", "Q!ZW", 1);
+        assert_eq!(
+            PublicKey::from_armored(&corrupted).unwrap_err(),
+            SerializationError::InvalidArmor
+        );
+
+        let hash = format!("{:016x}", params.id());
+        let other_hash = format!("{:016x}", other_params.id());
+        assert_ne!(hash, other_hash);
+        assert_eq!(
+            PublicKey::from_armored(&text.replace(&hash, &other_hash)).unwrap_err(),
+            SerializationError::ParametersMismatch
+        );
+
+        let text = secret_key.to_armored_unencrypted(&params);
+        assert_eq!(
+            SecretKey::from_armored(&text, &other_params).unwrap_err(),
+            SerializationError::ParametersMismatch
+        );
+    }
+}
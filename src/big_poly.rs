@@ -0,0 +1,179 @@
+use super::poly::Poly;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
+use std::cmp;
+use std::ops::{Add, Mul, Neg, Rem, Sub};
+
+/// A polynomial with arbitrary-precision integer coefficients.
+///
+/// This is a sibling of `Poly`, used where intermediate values don't fit in an `i64`
+/// (such as tensor products, or the `p * q` modulus of Relinearization Version 2),
+/// and where scaling by a rational number must be exact rather than done in `f64`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BigPoly(Vec<BigInt>);
+
+impl Add<BigPoly> for BigPoly {
+    type Output = BigPoly;
+    fn add(self, other: BigPoly) -> Self::Output {
+        let max_degree = cmp::max(self.degree(), other.degree());
+
+        let out_val = (0..max_degree)
+            .map(|i| {
+                let self_i = self.0.get(i).cloned().unwrap_or_default();
+                let other_i = other.0.get(i).cloned().unwrap_or_default();
+                self_i + other_i
+            })
+            .collect();
+        BigPoly(out_val)
+    }
+}
+
+impl Sub<BigPoly> for BigPoly {
+    type Output = BigPoly;
+    fn sub(self, other: BigPoly) -> Self::Output {
+        self + -other
+    }
+}
+
+impl Neg for BigPoly {
+    type Output = Self;
+    fn neg(mut self) -> Self::Output {
+        for v in self.0.iter_mut() {
+            *v = -&*v;
+        }
+        self
+    }
+}
+
+impl Mul<BigPoly> for BigPoly {
+    type Output = BigPoly;
+    fn mul(self, other: BigPoly) -> Self::Output {
+        let mut out_val = vec![BigInt::zero(); self.0.len() + other.0.len() - 1];
+        for (i, self_i) in self.0.iter().enumerate() {
+            for (j, other_j) in other.0.iter().enumerate() {
+                out_val[i + j] += self_i * other_j;
+            }
+        }
+        BigPoly(out_val)
+    }
+}
+
+impl Rem<(&BigInt, usize)> for BigPoly {
+    type Output = BigPoly;
+    fn rem(self, modulus: (&BigInt, usize)) -> Self::Output {
+        let coeff_mod = modulus.0;
+        let degree = modulus.1;
+        let out_val = self
+            .fold(degree)
+            .0
+            .into_iter()
+            .map(|coeff| coeff.mod_floor(coeff_mod))
+            .collect();
+        BigPoly(out_val)
+    }
+}
+
+impl From<&Poly> for BigPoly {
+    fn from(poly: &Poly) -> BigPoly {
+        BigPoly(poly.val().into_iter().map(BigInt::from).collect())
+    }
+}
+
+impl BigPoly {
+    pub fn new(val: Vec<BigInt>) -> BigPoly {
+        BigPoly(val)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn val(&self) -> Vec<BigInt> {
+        self.0.clone()
+    }
+
+    // Take the polynomial mod (X^N + 1), without reducing the coefficients.
+    pub(crate) fn fold(&self, degree: usize) -> BigPoly {
+        let mut out_val = vec![BigInt::zero(); degree];
+        for (i, coeff) in self.0.iter().enumerate() {
+            // $ X^i == X^{i + j * 2N} mod (X^N + 1) for all j $, and $ X^N == -1 mod (X^N + 1) $
+            let reduced_i = i % (2 * degree);
+            if reduced_i >= degree {
+                out_val[reduced_i % degree] -= coeff;
+            } else {
+                out_val[reduced_i] += coeff;
+            }
+        }
+        BigPoly(out_val)
+    }
+
+    // Multiply each coefficient by num / den and round to the nearest integer, exactly.
+    pub(crate) fn scale_round(&self, num: &BigInt, den: &BigInt) -> BigPoly {
+        BigPoly(self.0.iter().map(|x| div_round(&(x * num), den)).collect())
+    }
+
+    // Convert back to a polynomial with i64 coefficients.
+    // The coefficients must fit in an i64 (e.g. after reducing mod q).
+    pub(crate) fn to_poly(&self) -> Poly {
+        Poly::new(
+            self.0
+                .iter()
+                .map(|x| x.to_i64().expect("coefficient does not fit in an i64"))
+                .collect(),
+        )
+    }
+}
+
+/// Compute round(x / d) exactly, for d > 0, rounding halves up.
+pub(crate) fn div_round(x: &BigInt, d: &BigInt) -> BigInt {
+    // round(x / d) = floor((2x + d) / 2d)
+    let numerator: BigInt = x * 2 + d;
+    numerator.div_floor(&(d * 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(val: Vec<i64>) -> BigPoly {
+        BigPoly(val.into_iter().map(BigInt::from).collect())
+    }
+
+    #[test]
+    fn big_poly_arithmetic_test() {
+        let a = Poly::new(vec![-7, 0, 0, 3, -1, 6, -3, 5, 9, -5]);
+        let b = Poly::new(vec![-1, -1, 0, 1, 0, -1, 1, 1, -1, -1]);
+        let (a_big, b_big) = (BigPoly::from(&a), BigPoly::from(&b));
+
+        assert_eq!(
+            (a_big.clone() + b_big.clone()).to_poly(),
+            a.clone() + b.clone()
+        );
+        assert_eq!(
+            (a_big.clone() - b_big.clone()).to_poly(),
+            a.clone() - b.clone()
+        );
+        assert_eq!((-a_big.clone()).to_poly(), -a.clone());
+        assert_eq!(
+            (a_big.clone() * b_big.clone()).to_poly(),
+            a.clone() * b.clone()
+        );
+        assert_eq!(
+            ((a_big * b_big) % (&BigInt::from(16), 8)).to_poly(),
+            (a * b) % (16, 8)
+        );
+    }
+
+    #[test]
+    fn scale_round_test() {
+        let x = big(vec![49, 24, 25, -26, -25, 0]);
+        let scaled = x.scale_round(&BigInt::from(2), &BigInt::from(100));
+        assert_eq!(scaled, big(vec![1, 0, 1, -1, 0, 0]));
+
+        // Scaling is exact even when the coefficients don't fit in an f64 mantissa.
+        let q = BigInt::from(1) << 200;
+        let x = BigPoly::new(vec![(BigInt::from(3) << 198) + 1]);
+        assert_eq!(x.scale_round(&BigInt::from(4), &q), big(vec![3]));
+    }
+}
@@ -1,20 +1,31 @@
-use super::keys::{RelinearizationKey1, RelinearizationKey2, SecretKey};
+use super::big_poly::BigPoly;
+use super::error::{self, Error};
+use super::keys::{RelinearizationKey1, RelinearizationKey2, RnsRelinearizationKey, SecretKey};
+use super::noise;
+use super::params::{Parameters, RnsParameters};
 use super::plaintext::Plaintext;
 use super::poly::Poly;
+use super::random_source::Seeded;
+use super::rns::RnsPoly;
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 use std::ops::{Add, Mul, Neg, Sub};
 
 /// A BFV12 Ciphertext
 ///
-/// * `c_0` = `[p_0 * u + e_1 + delta * m]_q`
-/// * `c_1` = `[p_1 * u + e_2]_q`
-/// * `q` = the ciphertext modulus
-/// * `t` = the plaintext modulus
+/// * `c_0` = `[p_0 * u + e_1 + delta * m]_q`, or `[-(a * s + e) + delta * m]_q` when encrypted
+///   with the secret key (see `SecretKey::encrypt`)
+/// * `c_1` = `[p_1 * u + e_2]_q`, or `a` when encrypted with the secret key
+/// * `params` = the parameters, including the ciphertext modulus q and the plaintext modulus t
+/// * `key_id` = the identifier of the secret key it was encrypted under
+/// * `noise_bound` = an upper bound on the invariant noise, tracked through each operation
 #[derive(Clone, Debug)]
 pub struct Ciphertext {
     pub(crate) c_0: Poly,
     pub(crate) c_1: Poly,
-    pub(crate) q: i64,
-    pub(crate) t: i64,
+    pub(crate) params: Parameters,
+    pub(crate) key_id: u64,
+    pub(crate) noise_bound: f64,
 }
 
 impl Ciphertext {
@@ -23,47 +34,387 @@ impl Ciphertext {
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
-    /// # let std_dev = 3.2;
-    /// # let degree = 4;
-    /// # let q = 65536;
-    /// # let t = 4;
     /// #
-    /// use bfv12::{Plaintext, SecretKey};
-    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// use bfv12::{Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], params.t());
     ///
-    /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
     ///
-    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// let ct = pt.encrypt(&public_key, &mut rng);
     /// let decrypted = ct.decrypt(&secret_key);
     ///
     /// assert_eq!(decrypted, pt);
     /// ```
     pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
+        self.try_decrypt(secret_key)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Decrypt a ciphertext as in `decrypt`, returning an error instead of panicking if it was
+    /// encrypted under a different secret key
+    pub fn try_decrypt(&self, secret_key: &SecretKey) -> Result<Plaintext, Error> {
+        let raw = self.raw_decrypt(secret_key)?;
+        Ok(self.decode(raw))
+    }
+
+    /// Decrypt a ciphertext as in `try_decrypt`, but return an error if the noise has likely
+    /// overflowed, so that the plaintext would be wrong.
+    ///
+    /// The noise is only known modulo q / t: noise that has grown past q / (2t) looks like the
+    /// noise of a different plaintext, with coefficients spread over [0, q / (2t)]. So this fails
+    /// when the noise budget is 0, i.e. when the invariant noise is above about 1/4. For noise
+    /// that has overflowed, that happens except with probability about 2^-N.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(1024, 1 << 40, 16, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+    ///
+    /// let pt = Plaintext::rand(&params, &mut rng);
+    /// let ct = pt.encrypt(&public_key, &mut rng);
+    /// assert_eq!(ct.decrypt_checked(&secret_key).unwrap(), pt);
+    ///
+    /// // Squaring repeatedly exhausts the noise budget
+    /// let mut ct = ct;
+    /// for _ in 0..3 {
+    ///     ct = ct.clone() * (ct, &rlk_1);
+    /// }
+    /// match ct.decrypt_checked(&secret_key) {
+    ///     Err(Error::NoiseOverflow { noise }) => assert!(noise > 0.25),
+    ///     _ => panic!("expected the noise to overflow"),
+    /// }
+    /// ```
+    pub fn decrypt_checked(&self, secret_key: &SecretKey) -> Result<Plaintext, Error> {
+        let raw = self.raw_decrypt(secret_key)?;
+        let max = self.noise_max(&raw);
+        if self.budget(max) == 0 {
+            let noise = max as f64 / self.params.q() as f64;
+            return Err(Error::NoiseOverflow { noise });
+        }
+        Ok(self.decode(raw))
+    }
+
+    // Scale [c_0 + c_1 * s]_q by t/q and round to recover the plaintext.
+    fn decode(&self, raw: Poly) -> Plaintext {
+        let (degree, q, t) = (self.params.degree(), self.params.q(), self.params.t());
+        let poly = raw.scale_round(t, q) % (t, degree);
+        Plaintext::new_from_poly(poly, t)
+    }
+
+    /// The invariant noise budget in bits, as in SEAL: the number of bits by which the invariant
+    /// noise can grow before decryption fails. A ciphertext with a budget of 0 can't be expected
+    /// to decrypt correctly.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(1024, 1 << 40, 16, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+    ///
+    /// let ct_1 = Plaintext::rand(&params, &mut rng).encrypt(&public_key, &mut rng);
+    /// let ct_2 = Plaintext::rand(&params, &mut rng).encrypt(&public_key, &mut rng);
+    /// let fresh_budget = ct_1.noise_budget(&secret_key);
+    ///
+    /// let product = ct_1 * (ct_2, &rlk_1);
+    /// assert!(0 < product.noise_budget(&secret_key));
+    /// assert!(product.noise_budget(&secret_key) < fresh_budget);
+    /// ```
+    pub fn noise_budget(&self, secret_key: &SecretKey) -> u32 {
+        self.budget(self.invariant_noise_max(secret_key))
+    }
+
+    // The largest b with 2 * max * 2^b <= q, or 0, for the largest coefficient `max` of
+    // [t * (c_0 + c_1 * s)]_q
+    fn budget(&self, max: i128) -> u32 {
+        let q = self.params.q() as i128;
+        let ratio = q / (2 * max.max(1));
+        if ratio == 0 {
+            0
+        } else {
+            127 - ratio.leading_zeros()
+        }
+    }
+
+    /// The infinity norm of the invariant noise v, as in SEAL, where
+    /// `(t / q) * (c_0 + c_1 * s) = m + v + t * r` for some polynomial r.
+    /// Decryption is correct while `v < 1/2`.
+    pub fn invariant_noise(&self, secret_key: &SecretKey) -> f64 {
+        self.invariant_noise_max(secret_key) as f64 / self.params.q() as f64
+    }
+
+    // The largest coefficient of [t * (c_0 + c_1 * s)]_q, lifted to (-q/2, q/2], in absolute value.
+    // This is q times the infinity norm of the invariant noise.
+    fn invariant_noise_max(&self, secret_key: &SecretKey) -> i128 {
+        let raw = self
+            .raw_decrypt(secret_key)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.noise_max(&raw)
+    }
+
+    // The largest coefficient of [t * raw]_q, lifted to (-q/2, q/2], in absolute value
+    fn noise_max(&self, raw: &Poly) -> i128 {
+        let (q, t) = (self.params.q() as i128, self.params.t() as i128);
+        raw.val()
+            .iter()
+            .map(|c| {
+                let scaled = (*c as i128 * t) % q;
+                scaled.min(q - scaled)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    // [c_0 + c_1 * s]_q, with coefficients in [0, q)
+    fn raw_decrypt(&self, secret_key: &SecretKey) -> Result<Poly, Error> {
         let s = &secret_key.poly;
-        let degree = s.degree();
+        let (degree, q) = (self.params.degree(), self.params.q());
+        error::check_key(self.key_id, secret_key.id)?;
+        error::check_degree(degree, s.degree())?;
+
+        Ok((self.c_0.clone() + self.params.mul_mod(&self.c_1, s)) % (q, degree))
+    }
+
+    /// Add two ciphertexts as with `+`, returning an error instead of panicking if they were
+    /// encrypted under different parameters or keys
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Parameters, Plaintext, SecretKey};
+    /// let params_1 = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let params_2 = Parameters::new(4, 65536, 8, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params_1, &mut rng);
+    /// let ct_1 = secret_key.encrypt(&Plaintext::rand(&params_1, &mut rng), &params_1, &mut rng);
+    /// let ct_2 = secret_key.encrypt(&Plaintext::rand(&params_2, &mut rng), &params_2, &mut rng);
+    ///
+    /// assert_eq!(ct_1.try_add(ct_2).unwrap_err(), Error::ParametersMismatch);
+    /// ```
+    pub fn try_add(self, other: Ciphertext) -> Result<Ciphertext, Error> {
+        self.check_operand(&other)?;
+        let (q, degree) = (self.params.q(), self.params.degree());
+        Ok(Ciphertext {
+            c_0: (self.c_0 + other.c_0) % (q, degree),
+            c_1: (self.c_1 + other.c_1) % (q, degree),
+            params: self.params,
+            key_id: self.key_id,
+            noise_bound: self.noise_bound + other.noise_bound,
+        })
+    }
+
+    /// Subtract a ciphertext as with `-`, returning an error instead of panicking if they were
+    /// encrypted under different parameters or keys
+    pub fn try_sub(self, other: Ciphertext) -> Result<Ciphertext, Error> {
+        self.check_operand(&other)?;
+        let (q, degree) = (self.params.q(), self.params.degree());
+        Ok(Ciphertext {
+            c_0: (self.c_0 - other.c_0) % (q, degree),
+            c_1: (self.c_1 - other.c_1) % (q, degree),
+            params: self.params,
+            key_id: self.key_id,
+            noise_bound: self.noise_bound + other.noise_bound,
+        })
+    }
+
+    /// Multiply two ciphertexts using Relinearization Version 1 as with `*`, returning an error
+    /// instead of panicking if the ciphertexts and the key have different parameters or keys
+    pub fn try_mul_1(
+        self,
+        other: Ciphertext,
+        rlk_1: &RelinearizationKey1,
+    ) -> Result<Ciphertext, Error> {
+        self.check_operand(&other)?;
+        error::check_params(&self.params, &rlk_1.params)?;
+        error::check_key(self.key_id, rlk_1.id)?;
+
+        let noise_bound = self.mul_1_noise_bound(&other, rlk_1);
+        let (c_0, c_1, c_2) = self.basic_mul(other);
+
+        Ok(self.relinearization_1(c_0, c_1, c_2, rlk_1, noise_bound))
+    }
+
+    /// Multiply two ciphertexts using Relinearization Version 2 as with `*`, returning an error
+    /// instead of panicking if the ciphertexts and the key have different parameters or keys
+    pub fn try_mul_2(
+        self,
+        other: Ciphertext,
+        rlk_2: &RelinearizationKey2,
+    ) -> Result<Ciphertext, Error> {
+        self.check_operand(&other)?;
+        error::check_params(&self.params, &rlk_2.params)?;
+        error::check_key(self.key_id, rlk_2.id)?;
+
+        let noise_bound = self.mul_2_noise_bound(&other, rlk_2);
+        let (c_0, c_1, c_2) = self.basic_mul(other);
+
+        Ok(self.relinearization_2(c_0, c_1, c_2, rlk_2, noise_bound))
+    }
+
+    /// Add a plaintext as with `+`, returning an error instead of panicking if the plaintext
+    /// modulus or degree don't match the ciphertext's parameters
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let ct = secret_key.encrypt(&Plaintext::rand(&params, &mut rng), &params, &mut rng);
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], 8);
+    ///
+    /// let err = ct.try_add_plain(&pt).unwrap_err();
+    /// assert_eq!(err, Error::PlaintextModulusMismatch { expected: 4, found: 8 });
+    /// ```
+    pub fn try_add_plain(self, plaintext: &Plaintext) -> Result<Ciphertext, Error> {
+        let delta_m = self.encode(plaintext)?;
+        let (q, degree) = (self.params.q(), self.params.degree());
+        Ok(Ciphertext {
+            c_0: (self.c_0 + delta_m) % (q, degree),
+            c_1: self.c_1,
+            noise_bound: noise::add_plain(&self.params, self.noise_bound),
+            params: self.params,
+            key_id: self.key_id,
+        })
+    }
+
+    /// Subtract a plaintext as with `-`, returning an error instead of panicking as in
+    /// `try_add_plain`
+    pub fn try_sub_plain(self, plaintext: &Plaintext) -> Result<Ciphertext, Error> {
+        let delta_m = self.encode(plaintext)?;
+        let (q, degree) = (self.params.q(), self.params.degree());
+        Ok(Ciphertext {
+            c_0: (self.c_0 - delta_m) % (q, degree),
+            c_1: self.c_1,
+            noise_bound: noise::add_plain(&self.params, self.noise_bound),
+            params: self.params,
+            key_id: self.key_id,
+        })
+    }
+
+    // delta * m for a plaintext m, checked against the parameters, with m reduced to [0, t)
+    fn encode(&self, plaintext: &Plaintext) -> Result<Poly, Error> {
+        plaintext.check(&self.params)?;
+        let (t, degree) = (self.params.t(), self.params.degree());
+        Ok((plaintext.poly() % (t, degree)) * self.params.delta())
+    }
+
+    /// Multiply by a plaintext as with `*`, returning an error instead of panicking if the
+    /// plaintext modulus or degree don't match the ciphertext's parameters
+    pub fn try_mul_plain(self, plaintext: &Plaintext) -> Result<Ciphertext, Error> {
+        plaintext.check(&self.params)?;
+        let (degree, t) = (self.params.degree(), self.params.t());
+
+        // Lift the plaintext to (-t/2, t/2], which keeps the noise growth to a factor of N * t/2.
+        let lifted: Vec<i64> = (plaintext.poly() % (t, degree))
+            .val()
+            .iter()
+            .map(|&c| if c > t / 2 { c - t } else { c })
+            .collect();
+        let m = Poly::new(lifted);
+
+        Ok(Ciphertext {
+            c_0: self.params.mul_mod(&self.c_0, &m),
+            c_1: self.params.mul_mod(&self.c_1, &m),
+            noise_bound: noise::mul_plain(&self.params, self.noise_bound),
+            params: self.params,
+            key_id: self.key_id,
+        })
+    }
+
+    /// An upper bound on the infinity norm of the invariant noise (see `invariant_noise`),
+    /// tracked through encryption and each operation without the secret key. The bound is
+    /// worst-case, so it is typically several bits above the measured noise.
+    pub fn noise_bound(&self) -> f64 {
+        self.noise_bound
+    }
 
-        let delta_inv = self.t as f64 / self.q as f64;
-        let raw = (self.c_0.clone() + self.c_1.clone() * s.clone()) % (self.q, degree);
-        let poly = (raw * delta_inv) % (self.t, degree);
+    /// The noise budget in bits implied by `noise_bound`, which is at most the budget measured
+    /// with `noise_budget`. Decryption is guaranteed to be correct while it is above 0.
+    pub fn estimated_noise_budget(&self) -> u32 {
+        noise::budget(self.noise_bound)
+    }
 
-        Plaintext::new_from_poly(poly, self.t)
+    /// The noise bound of the product with `other` using Relinearization Version 1, to decide
+    /// whether the multiplication is safe before performing it. The product is guaranteed to
+    /// decrypt correctly if the bound is below 1/2.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(1024, 1 << 50, 16, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+    ///
+    /// let ct_1 = Plaintext::rand(&params, &mut rng).encrypt(&public_key, &mut rng);
+    /// let ct_2 = Plaintext::rand(&params, &mut rng).encrypt(&public_key, &mut rng);
+    /// assert!(ct_1.mul_1_noise_bound(&ct_2, &rlk_1) < 0.5);
+    ///
+    /// let product = ct_1 * (ct_2, &rlk_1);
+    /// assert!(0 < product.estimated_noise_budget());
+    /// assert!(product.estimated_noise_budget() <= product.noise_budget(&secret_key));
+    /// ```
+    pub fn mul_1_noise_bound(&self, other: &Ciphertext, rlk_1: &RelinearizationKey1) -> f64 {
+        noise::mul(&self.params, self.noise_bound, other.noise_bound)
+            + noise::relinearization_1(&rlk_1.params)
     }
 
+    /// The noise bound of the product with `other` using Relinearization Version 2, as in
+    /// `mul_1_noise_bound`
+    pub fn mul_2_noise_bound(&self, other: &Ciphertext, rlk_2: &RelinearizationKey2) -> f64 {
+        noise::mul(&self.params, self.noise_bound, other.noise_bound)
+            + noise::relinearization_2(&rlk_2.params, rlk_2.p)
+    }
+
+    /// The parameters this ciphertext was encrypted under
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this ciphertext was encrypted under
+    pub fn key_id(&self) -> u64 {
+        self.key_id
+    }
+
+    // Check that another ciphertext belongs to the same parameters and key.
+    fn check_operand(&self, other: &Ciphertext) -> Result<(), Error> {
+        error::check_params(&self.params, &other.params)?;
+        error::check_key(self.key_id, other.key_id)
+    }
+
+    // The parameters of the ciphertexts must match.
     pub(crate) fn basic_mul(&self, other: Ciphertext) -> (Poly, Poly, Poly) {
-        let degree = self.c_0.degree();
-        assert_eq!(degree, self.c_1.degree());
-        assert_eq!(degree, other.c_0.degree());
-        assert_eq!(degree, other.c_1.degree());
+        let degree = self.params.degree();
 
-        let out_0_raw = self.c_0.clone() * other.c_0.clone();
-        let out_1_raw = self.c_0.clone() * other.c_1.clone() + self.c_1.clone() * other.c_0.clone();
-        let out_2_raw = self.c_1.clone() * other.c_1.clone();
+        let out_0_raw = self.c_0.mul_negacyclic(&other.c_0, degree);
+        let out_1_raw = self.c_0.mul_negacyclic(&other.c_1, degree)
+            + self.c_1.mul_negacyclic(&other.c_0, degree);
+        let out_2_raw = self.c_1.mul_negacyclic(&other.c_1, degree);
 
-        let delta_inv = self.t as f64 / self.q as f64;
-        let out_0 = (out_0_raw * delta_inv) % (self.q, degree);
-        let out_1 = (out_1_raw * delta_inv) % (self.q, degree);
-        let out_2 = (out_2_raw * delta_inv) % (self.q, degree);
+        // Scale by t/q and round exactly, since the products can be much larger than q.
+        let (t, q) = (BigInt::from(self.params.t()), BigInt::from(self.params.q()));
+        let out_0 = (out_0_raw.scale_round(&t, &q) % (&q, degree)).to_poly();
+        let out_1 = (out_1_raw.scale_round(&t, &q) % (&q, degree)).to_poly();
+        let out_2 = (out_2_raw.scale_round(&t, &q) % (&q, degree)).to_poly();
 
         (out_0, out_1, out_2)
     }
@@ -74,31 +425,34 @@ impl Ciphertext {
         c_1: Poly,
         c_2: Poly,
         rlk: &RelinearizationKey1,
+        noise_bound: f64,
     ) -> Ciphertext {
-        let degree = c_0.degree();
+        let (degree, q) = (self.params.degree(), self.params.q());
 
         // Decompose c_2 in base T (rlk_base), such that:
         // $ c_2 = \sum_{i=0}^l c_2^(i) T^i $ with $ c_2^(i) \in R_T $
-        let c_2_dec: Vec<Poly> = c_2.decompose(rlk.l, rlk.base);
+        let c_2_dec: Vec<Poly> = c_2.decompose(rlk.l, rlk.params.rlk_base());
 
         // Calculate the contributions of the decomposed c_2 for c_0 and c_1.
         let mut c_2_0 = Poly::new(vec![0; degree]);
         let mut c_2_1 = Poly::new(vec![0; degree]);
-        for i in 0..(rlk.l as usize) {
+        // The sums are reduced at every step, so that they can't overflow for large q.
+        for (rlk_i, c_2_i) in rlk.levels().zip(c_2_dec.iter()) {
             // Calculate the sum of the first entry of the relinearization key and decomposed c_2:
             // $ \sum_{i=0}^l rlk[i][0] * c_2^(i) $
-            c_2_0 = c_2_0 + rlk.val[i].0.clone() * c_2_dec[i].clone();
+            c_2_0 = (c_2_0 + self.params.mul_mod(rlk_i.0, c_2_i)) % (q, degree);
 
             // Calculate the sum of the second entry of the relinearization key and decomposed c_2:
             // $ \sum_{i=0}^l rlk[i][1] * c_2^(i) $
-            c_2_1 = c_2_1 + rlk.val[i].1.clone() * c_2_dec[i].clone();
+            c_2_1 = (c_2_1 + self.params.mul_mod(rlk_i.1, c_2_i)) % (q, degree);
         }
 
         Ciphertext {
-            c_0: c_0 + c_2_0,
-            c_1: c_1 + c_2_1,
-            q: self.q,
-            t: self.t,
+            c_0: (c_0 + c_2_0) % (q, degree),
+            c_1: (c_1 + c_2_1) % (q, degree),
+            params: self.params.clone(),
+            key_id: self.key_id,
+            noise_bound,
         }
     }
 
@@ -108,43 +462,49 @@ impl Ciphertext {
         c_1: Poly,
         c_2: Poly,
         rlk: &RelinearizationKey2,
+        noise_bound: f64,
     ) -> Ciphertext {
-        let degree = c_0.degree();
-        let p = rlk.p as f64;
+        let degree = self.params.degree();
+        let (p, q_big) = (BigInt::from(rlk.p), BigInt::from(self.params.q()));
 
-        let c_2_0 = (c_2.clone() * rlk.rlk_0.clone() / p) % (self.q, degree);
-        let c_2_1 = (c_2.clone() * rlk.rlk_1.clone() / p) % (self.q, degree);
+        // The products are taken mod p * q, which can be larger than an i64, so they are
+        // computed with big integers, and divided by p exactly.
+        let c_2 = BigPoly::from(&c_2);
+        let c_2_0_raw = (c_2.clone() * rlk.rlk_0.clone()).fold(degree);
+        let c_2_1_raw = (c_2 * rlk.rlk_1().clone()).fold(degree);
+        let c_2_0 = (c_2_0_raw.scale_round(&BigInt::one(), &p) % (&q_big, degree)).to_poly();
+        let c_2_1 = (c_2_1_raw.scale_round(&BigInt::one(), &p) % (&q_big, degree)).to_poly();
+        let q = self.params.q();
         Ciphertext {
-            c_0: (c_0 + c_2_0) % (self.q, degree),
-            c_1: (c_1 + c_2_1) % (self.q, degree),
-            q: self.q,
-            t: self.t,
+            c_0: (c_0 + c_2_0) % (q, degree),
+            c_1: (c_1 + c_2_1) % (q, degree),
+            params: self.params.clone(),
+            key_id: self.key_id,
+            noise_bound,
         }
     }
 }
 
-/// Add two ciphertexts. They can be of different degrees.
+/// Add two ciphertexts. They must have been encrypted under the same parameters and key.
 ///
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
 /// #
-/// # let t = 12;         // Plaintext modulus
-/// # let q = 65536;      // Ciphertext modulus
-/// # let std_dev = 3.2;  // Standard deviation for generating the error
-/// # let degree = 4;     // Degree of polynomials used for encoding and encrypting messages
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
 /// #
 /// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
 /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
 /// #
-/// let secret_key = SecretKey::generate(degree, &mut rng);
-/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
 ///
-/// let pt_1 = Plaintext::rand(degree, t, &mut rng);
-/// let pt_2 = Plaintext::rand(degree, t, &mut rng);
-/// let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng);
-/// let ct_2 = pt_2.encrypt(&public_key, std_dev, &mut rng);
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+/// let ct_2 = pt_2.encrypt(&public_key, &mut rng);
 ///
 /// // Add the ciphertexts: ct_1 + ct_2
 /// let add_ct = ct_1 + ct_2;
@@ -159,37 +519,31 @@ impl Ciphertext {
 impl Add<Ciphertext> for Ciphertext {
     type Output = Self;
     fn add(self, other: Ciphertext) -> Self::Output {
-        Ciphertext {
-            c_0: self.c_0 + other.c_0,
-            c_1: self.c_1 + other.c_1,
-            q: self.q,
-            t: self.t,
-        }
+        self.try_add(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
-/// Subtract one ciphertext from another. They can be of different degrees.
+/// Subtract one ciphertext from another. They must have been encrypted under the same
+/// parameters and key.
 ///
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
 /// #
-/// # let t = 12;         // Plaintext modulus
-/// # let q = 65536;      // Ciphertext modulus
-/// # let std_dev = 3.2;  // Standard deviation for generating the error
-/// # let degree = 4;     // Degree of polynomials used for encoding and encrypting messages
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
 /// #
 /// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
 /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
 /// #
-/// let secret_key = SecretKey::generate(degree, &mut rng);
-/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
 ///
-/// let pt_1 = Plaintext::rand(degree, t, &mut rng);
-/// let pt_2 = Plaintext::rand(degree, t, &mut rng);
-/// let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng);
-/// let ct_2 = pt_2.encrypt(&public_key, std_dev, &mut rng);
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+/// let ct_2 = pt_2.encrypt(&public_key, &mut rng);
 ///
 /// // Subtract: ct_1 - ct_2
 /// let sub_ct = ct_1 - ct_2;
@@ -204,12 +558,85 @@ impl Add<Ciphertext> for Ciphertext {
 impl Sub<Ciphertext> for Ciphertext {
     type Output = Self;
     fn sub(self, other: Ciphertext) -> Self::Output {
-        Ciphertext {
-            c_0: self.c_0 - other.c_0,
-            c_1: self.c_1 - other.c_1,
-            q: self.q,
-            t: self.t,
-        }
+        self.try_sub(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// Add a plaintext to a ciphertext, without encrypting it. The plaintext must have the same
+/// plaintext modulus and degree as the ciphertext's parameters.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// #
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
+/// #
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
+/// #
+/// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// #
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
+///
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+///
+/// // Add the plaintext to the ciphertext: ct_1 + pt_2
+/// let add_ct = ct_1 + &pt_2;
+///
+/// // Decrypt the result of the addition
+/// let add_pt = add_ct.decrypt(&secret_key);
+///
+/// // Compare the expected output to the decrypted output
+/// let expected_pt = (pt_1.poly() + pt_2.poly()) % (t, degree);
+/// assert_eq!(add_pt.poly(), expected_pt)
+/// ```
+impl Add<&Plaintext> for Ciphertext {
+    type Output = Self;
+    fn add(self, other: &Plaintext) -> Self::Output {
+        self.try_add_plain(other)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// Subtract a plaintext from a ciphertext, without encrypting it. The plaintext must have the
+/// same plaintext modulus and degree as the ciphertext's parameters.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// #
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
+/// #
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
+/// #
+/// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// #
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
+///
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+///
+/// // Subtract the plaintext from the ciphertext: ct_1 - pt_2
+/// let sub_ct = ct_1 - &pt_2;
+///
+/// // Decrypt the result of the subtraction
+/// let sub_pt = sub_ct.decrypt(&secret_key);
+///
+/// // Compare the expected output to the decrypted output
+/// let expected_pt = (pt_1.poly() - pt_2.poly()) % (t, degree);
+/// assert_eq!(sub_pt.poly(), expected_pt)
+/// ```
+impl Sub<&Plaintext> for Ciphertext {
+    type Output = Self;
+    fn sub(self, other: &Plaintext) -> Self::Output {
+        self.try_sub_plain(other)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
@@ -218,21 +645,19 @@ impl Sub<Ciphertext> for Ciphertext {
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
 /// #
-/// # let t = 12;         // Plaintext modulus
-/// # let q = 65536;      // Ciphertext modulus
-/// # let std_dev = 3.2;  // Standard deviation for generating the error
-/// # let degree = 4;     // Degree of polynomials used for encoding and encrypting messages
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
 /// #
 /// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
 /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
 /// #
-/// let secret_key = SecretKey::generate(degree, &mut rng);
-/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
 ///
-/// let pt = Plaintext::rand(degree, t, &mut rng);
-/// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+/// let pt = Plaintext::rand(&params, &mut rng);
+/// let ct = pt.encrypt(&public_key, &mut rng);
 ///
 /// // Negate: -ct
 /// let neg_ct = -ct;
@@ -247,8 +672,9 @@ impl Sub<Ciphertext> for Ciphertext {
 impl Neg for Ciphertext {
     type Output = Self;
     fn neg(mut self) -> Self::Output {
-        self.c_0 = -self.c_0;
-        self.c_1 = -self.c_1;
+        let (q, degree) = (self.params.q(), self.params.degree());
+        self.c_0 = -self.c_0 % (q, degree);
+        self.c_1 = -self.c_1 % (q, degree);
         self
     }
 }
@@ -261,25 +687,22 @@ impl Neg for Ciphertext {
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
 /// #
-/// # let t = 12;         // Plaintext modulus
-/// # let q = 65536;      // Ciphertext modulus
-/// # let std_dev = 3.2;  // Standard deviation for generating the error
-/// # let degree = 4;     // Degree of polynomials used for encoding and encrypting messages
-/// # let rlk_base = (q as f64).log2() as i64; // The base for decomposition during relinearization
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
 /// #
 /// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
 /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
 /// #
-/// let secret_key = SecretKey::generate(degree, &mut rng);
-/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
-/// let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base);
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
+/// let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
 ///
-/// let pt_1 = Plaintext::rand(degree, t, &mut rng);
-/// let pt_2 = Plaintext::rand(degree, t, &mut rng);
-/// let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng);
-/// let ct_2 = pt_2.encrypt(&public_key, std_dev, &mut rng);
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+/// let ct_2 = pt_2.encrypt(&public_key, &mut rng);
 ///
 /// // Multiply the ciphertexts: ct_1 * ct_2
 /// let mul_ct = ct_1 * (ct_2, &rlk_1);
@@ -295,10 +718,8 @@ impl Mul<(Ciphertext, &RelinearizationKey1)> for Ciphertext {
     type Output = Self;
     fn mul(self, other: (Ciphertext, &RelinearizationKey1)) -> Self::Output {
         let (other_ct, rlk_1) = other;
-
-        let (c_0, c_1, c_2) = self.basic_mul(other_ct);
-
-        self.relinearization_1(c_0, c_1, c_2, rlk_1)
+        self.try_mul_1(other_ct, rlk_1)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
@@ -310,29 +731,26 @@ impl Mul<(Ciphertext, &RelinearizationKey1)> for Ciphertext {
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
 /// #
-/// # let t = 12;         // Plaintext modulus
-/// # let q = 65536;      // Ciphertext modulus
-/// # let std_dev = 3.2;  // Standard deviation for generating the error
-/// # let degree = 4;     // Degree of polynomials used for encoding and encrypting messages
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
 /// #
 /// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
 /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
 /// #
-/// let secret_key = SecretKey::generate(degree, &mut rng);
-/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
 ///
 /// // p = the amount to scale the modulus, during modulus switching
-/// // Technically p should be >= q^3 for security (see paper discussion on Relinearization Version 2),
-/// // but setting p = q^3 results in an overflow when taking p * q so we will test with a smaller p.
-/// let p = 2_i64.pow(13) * q;
-/// let rlk_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p);
+/// // p should be >= q^3 for security (see paper discussion on Relinearization Version 2).
+/// let p = params.q() * params.q() * params.q();
+/// let rlk_2 = secret_key.relin_key_gen_2(&params, p, &mut rng);
 ///
-/// let pt_1 = Plaintext::rand(degree, t, &mut rng);
-/// let pt_2 = Plaintext::rand(degree, t, &mut rng);
-/// let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng);
-/// let ct_2 = pt_2.encrypt(&public_key, std_dev, &mut rng);
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+/// let ct_2 = pt_2.encrypt(&public_key, &mut rng);
 ///
 /// // Multiply the ciphertexts: ct_1 * ct_2
 /// let mul_ct = ct_1 * (ct_2, &rlk_2);
@@ -348,9 +766,393 @@ impl Mul<(Ciphertext, &RelinearizationKey2)> for Ciphertext {
     type Output = Self;
     fn mul(self, other: (Ciphertext, &RelinearizationKey2)) -> Self::Output {
         let (other_ct, rlk_2) = other;
+        self.try_mul_2(other_ct, rlk_2)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// Multiply a ciphertext by a plaintext. The plaintext must have the same plaintext modulus and
+/// degree as the ciphertext's parameters. No relinearization key is needed.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// #
+/// # use bfv12::{Parameters, SecretKey, Plaintext};
+/// #
+/// # let (t, degree) = (12, 4);
+/// # let params = Parameters::new(degree, 65536, t, 3.2).unwrap();
+/// #
+/// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// #
+/// let secret_key = SecretKey::generate(&params, &mut rng);
+/// let public_key = secret_key.public_key_gen(&params, &mut rng);
+///
+/// let pt_1 = Plaintext::rand(&params, &mut rng);
+/// let pt_2 = Plaintext::rand(&params, &mut rng);
+/// let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+///
+/// // Multiply the ciphertext by the plaintext: ct_1 * pt_2
+/// let mul_ct = ct_1 * &pt_2;
+///
+/// // Decrypt the result of the multiplication
+/// let mul_pt = mul_ct.decrypt(&secret_key);
+///
+/// // Compare the expected output to the decrypted output
+/// let expected_pt = (pt_1.poly() * pt_2.poly()) % (t, degree);
+/// assert_eq!(mul_pt.poly(), expected_pt)
+/// ```
+impl Mul<&Plaintext> for Ciphertext {
+    type Output = Self;
+    fn mul(self, other: &Plaintext) -> Self::Output {
+        self.try_mul_plain(other)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// A BFV12 Ciphertext encrypted with the secret key, with `c_1` stored as the seed it was
+/// sampled from (see `SecretKey::encrypt_compressed`)
+///
+/// * `c_0` = `[-(a * s + e) + delta * m]_q`
+/// * `c_1` = `a`, sampled uniformly from a seed
+/// * `params` = the parameters, including the ciphertext modulus q and the plaintext modulus t
+/// * `key_id` = the identifier of the secret key it was encrypted under
+#[derive(Clone, Debug)]
+pub struct CompressedCiphertext {
+    pub(crate) c_0: Poly,
+    pub(crate) c_1: Seeded<Poly>,
+    pub(crate) params: Parameters,
+    pub(crate) key_id: u64,
+}
+
+impl CompressedCiphertext {
+    /// Expand to a regular ciphertext, regenerating `c_1` from its seed
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{CompressedCiphertext, Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(1024, 1 << 40, 16, 3.2).unwrap();
+    /// let pt = Plaintext::rand(&params, &mut rng);
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let compressed = secret_key.encrypt_compressed(&pt, &params, &mut rng);
+    ///
+    /// let bytes = compressed.to_bytes();
+    /// let ct = CompressedCiphertext::from_bytes(&bytes).unwrap().expand();
+    /// assert_eq!(ct.decrypt(&secret_key), pt);
+    /// ```
+    pub fn expand(&self) -> Ciphertext {
+        let (degree, q) = (self.params.degree(), self.params.q());
 
-        let (c_0, c_1, c_2) = self.basic_mul(other_ct);
+        Ciphertext {
+            c_0: self.c_0.clone(),
+            c_1: self.c_1.get_uniform(q, degree).clone(),
+            params: self.params.clone(),
+            key_id: self.key_id,
+            noise_bound: noise::fresh_secret(&self.params),
+        }
+    }
 
-        self.relinearization_2(c_0, c_1, c_2, rlk_2)
+    /// The parameters this ciphertext was encrypted under
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this ciphertext was encrypted under
+    pub fn key_id(&self) -> u64 {
+        self.key_id
+    }
+}
+
+/// A BFV12 Ciphertext, with the ciphertext modulus q represented by an RNS basis
+///
+/// * `c_0` = `[p_0 * u + e_1 + delta * m]_q`
+/// * `c_1` = `[p_1 * u + e_2]_q`
+/// * `params` = the parameters the ciphertext was encrypted under
+/// * `key_id` = the identifier of the secret key the ciphertext was encrypted under
+///
+/// This supports ciphertext moduli of hundreds of bits, as needed for realistic security levels.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// #
+/// # use bfv12::{Plaintext, RnsBasis, RnsParameters, SecretKey};
+/// #
+/// # let t = 12;         // Plaintext modulus
+/// # let std_dev = 3.2;  // Standard deviation for generating the error
+/// # let degree = 1024;  // Degree of polynomials used for encoding and encrypting messages
+/// #
+/// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// #
+/// // A 200-bit ciphertext modulus, represented by four 50-bit primes
+/// let basis = RnsBasis::generate(4, 50, degree);
+/// let params = RnsParameters::new(&basis, t, std_dev).unwrap();
+///
+/// let secret_key = SecretKey::generate_rns(&params, &mut rng);
+/// let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+/// let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+///
+/// let pt_1 = Plaintext::new((0..degree as i64).map(|i| i % t).collect(), t);
+/// let pt_2 = Plaintext::new((0..degree as i64).map(|i| (i * 5) % t).collect(), t);
+/// let pt_3 = Plaintext::new((0..degree as i64).map(|i| (i * 7 + 3) % t).collect(), t);
+/// let ct_1 = pt_1.encrypt_rns(&public_key, &mut rng);
+/// let ct_2 = pt_2.encrypt_rns(&public_key, &mut rng);
+/// let ct_3 = pt_3.encrypt_rns(&public_key, &mut rng);
+///
+/// // Multiply and add the ciphertexts: ct_1 * ct_2 + ct_3
+/// let expr_ct = ct_1 * (ct_2, &relin_key) + ct_3;
+///
+/// // Decrypt the result of the evaluation
+/// let expr_pt = expr_ct.decrypt(&secret_key);
+///
+/// // Compare the expected output to the decrypted output
+/// let expected_pt = (pt_1.poly() * pt_2.poly() + pt_3.poly()) % (t, degree);
+/// assert_eq!(expr_pt.poly(), expected_pt)
+/// ```
+#[derive(Clone, Debug)]
+pub struct RnsCiphertext {
+    pub(crate) c_0: RnsPoly,
+    pub(crate) c_1: RnsPoly,
+    pub(crate) params: RnsParameters,
+    pub(crate) key_id: u64,
+}
+
+impl RnsCiphertext {
+    /// The parameters this ciphertext was encrypted under
+    pub fn params(&self) -> &RnsParameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this ciphertext was encrypted under
+    pub fn key_id(&self) -> u64 {
+        self.key_id
+    }
+
+    /// Decrypt a ciphertext to recover a plaintext, given a secret key
+    pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
+        self.try_decrypt(secret_key)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Decrypt a ciphertext as in `decrypt`, returning an error instead of panicking if it was
+    /// encrypted under a different secret key
+    pub fn try_decrypt(&self, secret_key: &SecretKey) -> Result<Plaintext, Error> {
+        let t = self.params.t();
+        let poly = self.raw_decrypt(secret_key)?.scale_round_mod_t(t);
+        Ok(Plaintext::new_from_poly(poly, t))
+    }
+
+    /// The invariant noise budget in bits, as in `Ciphertext::noise_budget`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Plaintext, RnsBasis, RnsParameters, SecretKey};
+    /// let basis = RnsBasis::generate(4, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 16, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate_rns(&params, &mut rng);
+    /// let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+    /// let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+    ///
+    /// let pt = Plaintext::new(vec![3; 1024], 16);
+    /// let ct = pt.encrypt_rns(&public_key, &mut rng);
+    /// let fresh_budget = ct.noise_budget(&secret_key);
+    ///
+    /// let square = ct.clone() * (ct, &relin_key);
+    /// assert!(0 < square.noise_budget(&secret_key));
+    /// assert!(square.noise_budget(&secret_key) < fresh_budget);
+    /// ```
+    pub fn noise_budget(&self, secret_key: &SecretKey) -> u32 {
+        let max = self.invariant_noise_max(secret_key).max(BigInt::one());
+        let ratio: BigInt = self.params.basis().modulus() / (max * 2);
+        if ratio.is_zero() {
+            0
+        } else {
+            ratio.bits() as u32 - 1
+        }
+    }
+
+    /// The infinity norm of the invariant noise v, as in `Ciphertext::invariant_noise`.
+    /// Decryption is correct while `v < 1/2`.
+    pub fn invariant_noise(&self, secret_key: &SecretKey) -> f64 {
+        let q = self.params.basis().modulus();
+        let max = self.invariant_noise_max(secret_key);
+        // q can be too large for an f64, in which case both are scaled down
+        let shift = q.bits().saturating_sub(f64::MAX_EXP as u64 - 1);
+        (max >> shift).to_f64().unwrap() / (q >> shift).to_f64().unwrap()
+    }
+
+    // The largest coefficient of [t * (c_0 + c_1 * s)]_q, lifted to (-q/2, q/2], in absolute value.
+    // This is q times the infinity norm of the invariant noise.
+    fn invariant_noise_max(&self, secret_key: &SecretKey) -> BigInt {
+        let raw = self
+            .raw_decrypt(secret_key)
+            .unwrap_or_else(|err| panic!("{}", err));
+        raw.mul_bigint(&BigInt::from(self.params.t()))
+            .to_centered_bigint()
+            .into_iter()
+            .map(|c| c.abs())
+            .max()
+            .unwrap_or_default()
+    }
+
+    // [c_0 + c_1 * s]_q
+    fn raw_decrypt(&self, secret_key: &SecretKey) -> Result<RnsPoly, Error> {
+        error::check_key(self.key_id, secret_key.id)?;
+        error::check_degree(self.params.degree(), secret_key.poly.degree())?;
+        let s = RnsPoly::from_poly(&secret_key.poly, self.params.basis());
+
+        Ok(self.c_0.clone() + self.c_1.clone() * s)
+    }
+
+    fn check_operand(&self, other: &RnsCiphertext) -> Result<(), Error> {
+        error::check_params(&self.params, &other.params)?;
+        error::check_key(self.key_id, other.key_id)
+    }
+
+    /// Add two RNS ciphertexts as with `+`, returning an error instead of panicking if they were
+    /// encrypted under different parameters, such as different plaintext moduli, or keys
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Plaintext, RnsBasis, RnsParameters, SecretKey};
+    /// let basis = RnsBasis::generate(2, 50, 4);
+    /// let params_1 = RnsParameters::new(&basis, 4, 3.2).unwrap();
+    /// let params_2 = RnsParameters::new(&basis, 8, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate_rns(&params_1, &mut rng);
+    /// let public_key_1 = secret_key.rns_public_key_gen(&params_1, &mut rng);
+    /// let public_key_2 = secret_key.rns_public_key_gen(&params_2, &mut rng);
+    /// let ct_1 = Plaintext::new(vec![0, 1, 2, 3], 4).encrypt_rns(&public_key_1, &mut rng);
+    /// let ct_2 = Plaintext::new(vec![0, 1, 2, 3], 8).encrypt_rns(&public_key_2, &mut rng);
+    ///
+    /// assert_eq!(ct_1.try_add(ct_2).unwrap_err(), Error::ParametersMismatch);
+    /// ```
+    pub fn try_add(self, other: RnsCiphertext) -> Result<RnsCiphertext, Error> {
+        self.check_operand(&other)?;
+        Ok(RnsCiphertext {
+            c_0: self.c_0 + other.c_0,
+            c_1: self.c_1 + other.c_1,
+            params: self.params,
+            key_id: self.key_id,
+        })
+    }
+
+    /// Subtract an RNS ciphertext as with `-`, returning an error instead of panicking if they
+    /// were encrypted under different parameters or keys
+    pub fn try_sub(self, other: RnsCiphertext) -> Result<RnsCiphertext, Error> {
+        self.check_operand(&other)?;
+        Ok(RnsCiphertext {
+            c_0: self.c_0 - other.c_0,
+            c_1: self.c_1 - other.c_1,
+            params: self.params,
+            key_id: self.key_id,
+        })
+    }
+
+    /// Multiply two RNS ciphertexts as with `*`, returning an error instead of panicking if the
+    /// ciphertexts and the key have different parameters or keys
+    pub fn try_mul(
+        self,
+        other: RnsCiphertext,
+        rlk: &RnsRelinearizationKey,
+    ) -> Result<RnsCiphertext, Error> {
+        self.check_operand(&other)?;
+        error::check_params(&self.params, &rlk.params)?;
+        error::check_key(self.key_id, rlk.id)?;
+
+        let (c_0, c_1, c_2) = self.basic_mul(other);
+
+        Ok(self.relinearization(c_0, c_1, c_2, rlk))
+    }
+
+    // Compute the tensor product [round(t/q * (ct_1 (x) ct_2))]_q.
+    // The products are computed exactly in the (larger) tensor basis before scaling back to q.
+    pub(crate) fn basic_mul(&self, other: RnsCiphertext) -> (RnsPoly, RnsPoly, RnsPoly) {
+        let (basis, t) = (self.params.basis(), self.params.t());
+        let (c_0, c_1) = (self.c_0.extend(), self.c_1.extend());
+        let (d_0, d_1) = (other.c_0.extend(), other.c_1.extend());
+
+        let out_0_raw = c_0.clone() * d_0.clone();
+        let out_1_raw = c_0 * d_1.clone() + c_1.clone() * d_0;
+        let out_2_raw = c_1 * d_1;
+
+        let out_0 = out_0_raw.scale_round(t, basis);
+        let out_1 = out_1_raw.scale_round(t, basis);
+        let out_2 = out_2_raw.scale_round(t, basis);
+
+        (out_0, out_1, out_2)
+    }
+
+    fn relinearization(
+        &self,
+        c_0: RnsPoly,
+        c_1: RnsPoly,
+        c_2: RnsPoly,
+        rlk: &RnsRelinearizationKey,
+    ) -> RnsCiphertext {
+        // Decompose c_2 into its RNS digits, such that:
+        // $ c_2 = \sum_{i=0}^k [c_2 * q_i~]_{q_i} * q_i^* mod q $
+        let c_2_dec = c_2.decompose();
+
+        let mut c_2_0 = RnsPoly::zero(c_2.basis());
+        let mut c_2_1 = RnsPoly::zero(c_2.basis());
+        for (rlk_i, c_2_i) in rlk.val.iter().zip(c_2_dec) {
+            c_2_0 = c_2_0 + rlk_i.0.clone() * c_2_i.clone();
+            c_2_1 = c_2_1 + rlk_i.1.clone() * c_2_i;
+        }
+
+        RnsCiphertext {
+            c_0: c_0 + c_2_0,
+            c_1: c_1 + c_2_1,
+            params: self.params.clone(),
+            key_id: self.key_id,
+        }
+    }
+}
+
+/// Add two RNS ciphertexts. They must have been encrypted under the same parameters and key.
+impl Add<RnsCiphertext> for RnsCiphertext {
+    type Output = Self;
+    fn add(self, other: RnsCiphertext) -> Self::Output {
+        self.try_add(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// Subtract one RNS ciphertext from another. They must have been encrypted under the same
+/// parameters and key.
+impl Sub<RnsCiphertext> for RnsCiphertext {
+    type Output = Self;
+    fn sub(self, other: RnsCiphertext) -> Self::Output {
+        self.try_sub(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// Take the negation of an RNS ciphertext.
+impl Neg for RnsCiphertext {
+    type Output = Self;
+    fn neg(mut self) -> Self::Output {
+        self.c_0 = -self.c_0;
+        self.c_1 = -self.c_1;
+        self
+    }
+}
+
+/// Multiply two RNS ciphertexts, relinearizing with the RNS decomposition.
+/// As with `Ciphertext`, you must multiply a ciphertext with a tuple of
+/// (RnsCiphertext, &RnsRelinearizationKey).
+impl Mul<(RnsCiphertext, &RnsRelinearizationKey)> for RnsCiphertext {
+    type Output = Self;
+    fn mul(self, other: (RnsCiphertext, &RnsRelinearizationKey)) -> Self::Output {
+        let (other_ct, rlk) = other;
+        self.try_mul(other_ct, rlk)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 }
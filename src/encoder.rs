@@ -0,0 +1,570 @@
+use super::error::Error;
+use super::ntt::NttTables;
+use super::params::{Parameters, ParametersError};
+use super::plaintext::Plaintext;
+use super::poly::Poly;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Encodes vectors of N integers mod t into the N slots of a plaintext, so that addition and
+/// multiplication of ciphertexts act on each slot independently (SIMD batching).
+///
+/// When t is a prime with `t = 1 mod 2N`, X^N + 1 splits into N linear factors mod t, and by the
+/// Chinese Remainder Theorem R_t is isomorphic to Z_t^N. The slots of a plaintext m are the
+/// evaluations of m at the N primitive 2N-th roots of unity mod t, i.e. its negacyclic NTT over
+/// Z_t, so encoding is an inverse NTT and decoding is a forward NTT.
+#[derive(Clone, Debug)]
+pub struct BatchEncoder {
+    t: i64,
+    degree: usize,
+    ntt: Arc<NttTables>,
+}
+
+impl BatchEncoder {
+    /// Create a batch encoder for the plaintext modulus and degree of the given parameters.
+    /// Returns an error unless t is a prime with `t = 1 mod 2N`.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    ///
+    /// ```rust
+    /// use bfv12::{BatchEncoder, Parameters};
+    ///
+    /// let params = Parameters::new(1024, 1 << 40, 12289, 3.2).unwrap();
+    /// assert!(BatchEncoder::new(&params).is_ok());
+    ///
+    /// let params = Parameters::new(1024, 1 << 40, 12, 3.2).unwrap();
+    /// assert!(BatchEncoder::new(&params).is_err());
+    /// ```
+    pub fn new(params: &Parameters) -> Result<BatchEncoder, ParametersError> {
+        let (t, degree) = (params.t(), params.degree());
+        let ntt =
+            NttTables::get(t, degree).ok_or(ParametersError::NotBatchingFriendly { t, degree })?;
+        Ok(BatchEncoder { t, degree, ntt })
+    }
+
+    /// The number of slots, which is the degree N
+    pub fn slot_count(&self) -> usize {
+        self.degree
+    }
+
+    /// Encode up to N integers into the slots of a plaintext. The values are reduced mod t,
+    /// and the remaining slots are set to 0.
+    ///
+    /// * `values`: the values of the slots
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{BatchEncoder, Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(1024, 1 << 58, 12289, 3.2).unwrap();
+    /// let encoder = BatchEncoder::new(&params).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let rlk = secret_key.relin_key_gen_1(&params, &mut rng);
+    ///
+    /// let ct_1 = encoder.encode(&[1, 2, 3, 4]).encrypt(&public_key, &mut rng);
+    /// let ct_2 = encoder.encode(&[5, 6, 7, -1]).encrypt(&public_key, &mut rng);
+    ///
+    /// let sum = encoder.decode(&(ct_1.clone() + ct_2.clone()).decrypt(&secret_key));
+    /// assert_eq!(sum[..4], [6, 8, 10, 3]);
+    /// let product = encoder.decode(&(ct_1 * (ct_2, &rlk)).decrypt(&secret_key));
+    /// assert_eq!(product[..4], [5, 12, 21, 12285]);
+    /// ```
+    pub fn encode(&self, values: &[i64]) -> Plaintext {
+        assert!(values.len() <= self.degree);
+        let mut slots = vec![0; self.degree];
+        for (slot, value) in slots.iter_mut().zip(values.iter()) {
+            *slot = value.rem_euclid(self.t);
+        }
+        // The forward NTT outputs the evaluations in bit-reversed order, which is used as the
+        // slot order, so the inverse NTT takes the slots as they are.
+        self.ntt.inverse(&mut slots);
+        Plaintext::new_from_poly(Poly::new(slots), self.t)
+    }
+
+    /// Decode the N slots of a plaintext, as integers in [0, t).
+    ///
+    /// * `plaintext`: a plaintext with the plaintext modulus and degree of this encoder
+    pub fn decode(&self, plaintext: &Plaintext) -> Vec<i64> {
+        self.try_decode(plaintext)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Decode the slots of a plaintext as in `decode`, returning an error instead of panicking
+    /// if its plaintext modulus or degree don't match the encoder.
+    ///
+    /// ```rust
+    /// use bfv12::{BatchEncoder, Error, Parameters, Plaintext};
+    ///
+    /// let params = Parameters::new(16, 1 << 40, 97, 3.2).unwrap();
+    /// let encoder = BatchEncoder::new(&params).unwrap();
+    ///
+    /// let err = encoder.try_decode(&Plaintext::new(vec![1; 16], 17)).unwrap_err();
+    /// assert_eq!(err, Error::PlaintextModulusMismatch { expected: 97, found: 17 });
+    /// ```
+    pub fn try_decode(&self, plaintext: &Plaintext) -> Result<Vec<i64>, Error> {
+        plaintext.check_with(self.t, self.degree)?;
+        let mut slots: Vec<i64> = plaintext
+            .poly()
+            .val()
+            .iter()
+            .map(|c| c.rem_euclid(self.t))
+            .collect();
+        self.ntt.forward(&mut slots);
+        Ok(slots)
+    }
+}
+
+/// Encodes signed integers into plaintexts, as the polynomial of their balanced base-b digits,
+/// so that ciphertext addition and multiplication act as integer addition and multiplication.
+///
+/// An integer x is encoded as `m(X) = sum_i d_i X^i` with `x = m(b)`, and digits d_i in
+/// [-b/2, b/2], stored mod t. Decoding lifts the coefficients of m to (-t/2, t/2] and evaluates
+/// m(b). Since `m_1(b) + m_2(b) = (m_1 + m_2)(b)` and `m_1(b) * m_2(b) = (m_1 * m_2)(b)`,
+/// the result decodes correctly as long as the polynomial arithmetic doesn't wrap around:
+///
+/// * every coefficient of the result must stay in (-t/2, t/2]. The sum of k encodings has
+///   coefficients of at most `k * b/2`, and the product of encodings with n_1 and n_2 digits
+///   has coefficients of at most `min(n_1, n_2) * (b/2)^2`.
+/// * the result must have degree below N, since X^N = -1 in R_t. The product of encodings
+///   with n_1 and n_2 digits has `n_1 + n_2 - 1` digits.
+/// * the decoded value must fit in an i64.
+#[derive(Clone, Debug)]
+pub struct IntegerEncoder {
+    t: i64,
+    degree: usize,
+    base: i64,
+}
+
+impl IntegerEncoder {
+    /// Create an integer encoder for the plaintext modulus and degree of the given parameters.
+    /// Returns an error unless the base is in [2, t), so that the digits are distinct mod t.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `base`: the base b of the digit expansion. A small base (e.g. 2 or 3) leaves the most
+    ///   room before the coefficients wrap around mod t.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{IntegerEncoder, Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(64, 1 << 40, 256, 3.2).unwrap();
+    /// let encoder = IntegerEncoder::new(&params, 3).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let rlk = secret_key.relin_key_gen_1(&params, &mut rng);
+    ///
+    /// let ct_1 = encoder.encode(-1234).encrypt(&public_key, &mut rng);
+    /// let ct_2 = encoder.encode(5678).encrypt(&public_key, &mut rng);
+    ///
+    /// let sum = ct_1.clone() + ct_2.clone();
+    /// assert_eq!(encoder.decode(&sum.decrypt(&secret_key)), 4444);
+    /// let product = ct_1 * (ct_2, &rlk);
+    /// assert_eq!(encoder.decode(&product.decrypt(&secret_key)), -7006652);
+    /// ```
+    pub fn new(params: &Parameters, base: i64) -> Result<IntegerEncoder, ParametersError> {
+        let (t, degree) = (params.t(), params.degree());
+        if base < 2 || base >= t {
+            return Err(ParametersError::InvalidEncodingBase { base, t });
+        }
+        Ok(IntegerEncoder { t, degree, base })
+    }
+
+    /// The base b of the digit expansion
+    pub fn base(&self) -> i64 {
+        self.base
+    }
+
+    /// Encode an integer as the polynomial of its balanced base-b digits.
+    /// Panics if the value has more than N digits.
+    ///
+    /// * `value`: the integer to encode
+    pub fn encode(&self, value: i64) -> Plaintext {
+        let digits = balanced_digits(value as i128, self.base);
+        assert!(
+            digits.len() <= self.degree,
+            "{} has more than N digits in base {}",
+            value,
+            self.base
+        );
+        let mut coefficients = vec![0; self.degree];
+        for (coefficient, digit) in coefficients.iter_mut().zip(digits.iter()) {
+            *coefficient = digit.rem_euclid(self.t);
+        }
+        Plaintext::new_from_poly(Poly::new(coefficients), self.t)
+    }
+
+    /// Decode a plaintext to the integer m(b), after lifting its coefficients to (-t/2, t/2].
+    /// Panics if the value doesn't fit in an i64.
+    ///
+    /// * `plaintext`: a plaintext with the plaintext modulus and degree of this encoder
+    pub fn decode(&self, plaintext: &Plaintext) -> i64 {
+        self.try_decode(plaintext)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Decode a plaintext as in `decode`, returning an error instead of panicking if its
+    /// plaintext modulus or degree don't match the encoder. Still panics if the value doesn't
+    /// fit in an i64.
+    pub fn try_decode(&self, plaintext: &Plaintext) -> Result<i64, Error> {
+        plaintext.check_with(self.t, self.degree)?;
+        let poly = plaintext.poly();
+
+        // Evaluate with Horner's rule, from the highest digit down. The partial sums of balanced
+        // digits can exceed the final value, so they are computed in i128.
+        let value = poly.val().iter().rev().try_fold(0i128, |acc, c| {
+            acc.checked_mul(self.base as i128)?
+                .checked_add(lift(*c, self.t) as i128)
+        });
+        Ok(value
+            .and_then(|value| i64::try_from(value).ok())
+            .expect("the decoded value overflows an i64"))
+    }
+}
+
+/// Encodes real numbers into plaintexts as fixed-point values, with the integer part in the low
+/// coefficients and the fractional part in the high coefficients, so that ciphertext addition and
+/// multiplication act as addition and multiplication of the real numbers.
+///
+/// A value x is rounded to `fraction_coeff_count` base-b digits after the point, and written with
+/// balanced digits as `x = sum_{i >= 0} d_i b^i + sum_{j >= 1} f_j b^-j`. Since `X^N = -1` in R_t,
+/// `X^-j = -X^(N - j)`, so the fractional digit f_j is stored as the coefficient -f_j of X^(N - j),
+/// and m(b) = x still holds with X^-j evaluated at b^-j. Decoding reads the low N/2 coefficients
+/// as the integer part, and the high N/2 coefficients as the fractional part.
+///
+/// The result decodes correctly as long as the coefficients stay in (-t/2, t/2] (see
+/// `IntegerEncoder`), and the integer and fractional digits don't meet in the middle: the product
+/// of k encodings has up to `k * integer_coeff_count` integer digits and
+/// `k * fraction_coeff_count` fractional digits, which must each be at most N/2.
+#[derive(Clone, Debug)]
+pub struct FractionalEncoder {
+    t: i64,
+    degree: usize,
+    base: i64,
+    integer_coeff_count: usize,
+    fraction_coeff_count: usize,
+}
+
+impl FractionalEncoder {
+    /// Create a fractional encoder for the plaintext modulus and degree of the given parameters.
+    /// Returns an error unless the base is in [2, t), and each coefficient count is at most N/2.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `base`: the base b of the digit expansion
+    /// * `integer_coeff_count`: the maximum number of digits of the integer part
+    /// * `fraction_coeff_count`: the number of digits after the point, so that values are
+    ///   encoded with precision `b^-fraction_coeff_count`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{FractionalEncoder, Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(128, 1 << 40, 256, 3.2).unwrap();
+    /// let encoder = FractionalEncoder::new(&params, 2, 16, 16).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let rlk = secret_key.relin_key_gen_1(&params, &mut rng);
+    ///
+    /// let ct_1 = encoder.encode(12.375).encrypt(&public_key, &mut rng);
+    /// let ct_2 = encoder.encode(-3.25).encrypt(&public_key, &mut rng);
+    ///
+    /// let sum = ct_1.clone() + ct_2.clone();
+    /// assert_eq!(encoder.decode(&sum.decrypt(&secret_key)), 9.125);
+    /// let product = ct_1 * (ct_2, &rlk);
+    /// assert_eq!(encoder.decode(&product.decrypt(&secret_key)), -40.21875);
+    /// ```
+    pub fn new(
+        params: &Parameters,
+        base: i64,
+        integer_coeff_count: usize,
+        fraction_coeff_count: usize,
+    ) -> Result<FractionalEncoder, ParametersError> {
+        let (t, degree) = (params.t(), params.degree());
+        if base < 2 || base >= t {
+            return Err(ParametersError::InvalidEncodingBase { base, t });
+        }
+        if integer_coeff_count > degree / 2 || fraction_coeff_count > degree / 2 {
+            return Err(ParametersError::InvalidCoefficientCounts {
+                integer_coeff_count,
+                fraction_coeff_count,
+                degree,
+            });
+        }
+        Ok(FractionalEncoder {
+            t,
+            degree,
+            base,
+            integer_coeff_count,
+            fraction_coeff_count,
+        })
+    }
+
+    /// Encode a real number, rounded to `fraction_coeff_count` digits after the point.
+    /// Panics if the value is not finite, or its integer part has more than
+    /// `integer_coeff_count` digits.
+    ///
+    /// * `value`: the real number to encode
+    pub fn encode(&self, value: f64) -> Plaintext {
+        assert!(value.is_finite());
+        // Encode the integer round(x * b^k), whose digits below b^k are the fractional digits.
+        let scale = (self.base as f64).powi(self.fraction_coeff_count as i32);
+        let scaled = (value * scale).round();
+        assert!(
+            scaled.abs() < 2f64.powi(126),
+            "{} is too large to encode",
+            value
+        );
+        let digits = balanced_digits(scaled as i128, self.base);
+        assert!(
+            digits.len() <= self.integer_coeff_count + self.fraction_coeff_count,
+            "the integer part of {} has more than {} digits in base {}",
+            value,
+            self.integer_coeff_count,
+            self.base
+        );
+
+        let mut coefficients = vec![0; self.degree];
+        for (i, digit) in digits.iter().enumerate() {
+            if i >= self.fraction_coeff_count {
+                // The integer digit of b^(i - k)
+                coefficients[i - self.fraction_coeff_count] = digit.rem_euclid(self.t);
+            } else {
+                // The fractional digit of b^-j, for j = k - i
+                let j = self.fraction_coeff_count - i;
+                coefficients[self.degree - j] = (-digit).rem_euclid(self.t);
+            }
+        }
+        Plaintext::new_from_poly(Poly::new(coefficients), self.t)
+    }
+
+    /// Decode a plaintext to a real number, after lifting its coefficients to (-t/2, t/2].
+    ///
+    /// * `plaintext`: a plaintext with the plaintext modulus and degree of this encoder
+    pub fn decode(&self, plaintext: &Plaintext) -> f64 {
+        self.try_decode(plaintext)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Decode a plaintext as in `decode`, returning an error instead of panicking if its
+    /// plaintext modulus or degree don't match the encoder.
+    pub fn try_decode(&self, plaintext: &Plaintext) -> Result<f64, Error> {
+        plaintext.check_with(self.t, self.degree)?;
+        let coefficients = plaintext.poly().val();
+        let (integer_part, fraction_part) = coefficients.split_at(self.degree / 2);
+
+        // Evaluate both parts with Horner's rule, from the most significant digit down, so that
+        // the small fractional digits aren't lost to rounding.
+        let integer = integer_part.iter().rev().fold(0.0, |acc, c| {
+            acc * self.base as f64 + lift(*c, self.t) as f64
+        });
+        // fraction_part[i] is the coefficient of X^(N/2 + i) = -X^-(N/2 - i)
+        let fraction = fraction_part.iter().fold(0.0, |acc, c| {
+            (acc - lift(*c, self.t) as f64) / self.base as f64
+        });
+        Ok(integer + fraction)
+    }
+}
+
+// The balanced base-b digits of a value, from the least significant, with each digit in
+// [-b/2, b/2]. On a tie the digit takes the sign of the value, so that the rest shrinks to 0.
+fn balanced_digits(value: i128, base: i64) -> Vec<i64> {
+    let base = base as i128;
+    let mut rest = value;
+    let mut digits = Vec::new();
+    while rest != 0 {
+        let mut digit = rest.rem_euclid(base);
+        if 2 * digit > base || (2 * digit == base && rest < 0) {
+            digit -= base;
+        }
+        digits.push(digit as i64);
+        rest = (rest - digit) / base;
+    }
+    digits
+}
+
+// Lift a coefficient mod t to (-t/2, t/2].
+fn lift(coefficient: i64, t: i64) -> i64 {
+    let c = coefficient.rem_euclid(t);
+    if 2 * c > t {
+        c - t
+    } else {
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn encode_decode_test() {
+        let params = Parameters::new(16, 1 << 40, 97, 3.2).unwrap();
+        let encoder = BatchEncoder::new(&params).unwrap();
+        assert_eq!(encoder.slot_count(), 16);
+
+        let values: Vec<i64> = (0..16).map(|i| i * i - 50).collect();
+        let expected: Vec<i64> = values.iter().map(|v| v.rem_euclid(97)).collect();
+        assert_eq!(encoder.decode(&encoder.encode(&values)), expected);
+
+        // A constant polynomial has the same value in every slot
+        assert_eq!(
+            encoder.decode(&Plaintext::new([vec![5], vec![0; 15]].concat(), 97)),
+            vec![5; 16]
+        );
+    }
+
+    #[test]
+    fn slot_wise_operations_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(25);
+        let (degree, t) = (16, 97);
+        let params = Parameters::new(degree, 1 << 40, t, 3.2).unwrap();
+        let encoder = BatchEncoder::new(&params).unwrap();
+
+        let a = Plaintext::rand(&params, &mut rng);
+        let b = Plaintext::rand(&params, &mut rng);
+        let (a_slots, b_slots) = (encoder.decode(&a), encoder.decode(&b));
+
+        // Multiplication in R_t is slot-wise multiplication
+        let product = Plaintext::new_from_poly((a.poly() * b.poly()) % (t, degree), t);
+        let expected: Vec<i64> = a_slots
+            .iter()
+            .zip(b_slots.iter())
+            .map(|(x, y)| x * y % t)
+            .collect();
+        assert_eq!(encoder.decode(&product), expected);
+    }
+
+    #[test]
+    fn integer_encode_decode_test() {
+        let params = Parameters::new(64, 1 << 40, 256, 3.2).unwrap();
+        for base in [2, 3, 10, 16, 255].iter() {
+            let encoder = IntegerEncoder::new(&params, *base).unwrap();
+            for value in [0, 1, -1, 7, -8, 1 << 20, -123_456_789, i64::MAX, i64::MIN].iter() {
+                let plaintext = encoder.encode(*value);
+                assert_eq!(encoder.decode(&plaintext), *value);
+                // The digits are balanced
+                assert!(plaintext.poly().val().iter().all(|c| {
+                    let digit = if 2 * c > 256 { c - 256 } else { *c };
+                    2 * digit.abs() <= *base
+                }));
+            }
+        }
+
+        // Balanced digits: -1 = -1 in base 3, 5 = 1 * 3^2 - 1 * 3^1 - 1
+        let encoder = IntegerEncoder::new(&params, 3).unwrap();
+        assert_eq!(encoder.encode(-1).poly().val()[..2], [255, 0]);
+        assert_eq!(encoder.encode(5).poly().val()[..3], [255, 255, 1]);
+
+        assert_eq!(
+            IntegerEncoder::new(&params, 256).unwrap_err(),
+            ParametersError::InvalidEncodingBase { base: 256, t: 256 }
+        );
+    }
+
+    #[test]
+    fn integer_arithmetic_test() {
+        let (degree, t) = (64, 1024);
+        let params = Parameters::new(degree, 1 << 40, t, 3.2).unwrap();
+        let encoder = IntegerEncoder::new(&params, 2).unwrap();
+        let values = [-1000, -37, 0, 1, 255, 40_000];
+        for a in values.iter() {
+            for b in values.iter() {
+                let (pt_a, pt_b) = (encoder.encode(*a), encoder.encode(*b));
+                let sum = Plaintext::new_from_poly((pt_a.poly() + pt_b.poly()) % (t, degree), t);
+                assert_eq!(encoder.decode(&sum), a + b);
+                let product =
+                    Plaintext::new_from_poly((pt_a.poly() * pt_b.poly()) % (t, degree), t);
+                assert_eq!(encoder.decode(&product), a * b);
+            }
+        }
+    }
+
+    #[test]
+    fn fractional_encode_decode_test() {
+        let params = Parameters::new(64, 1 << 40, 256, 3.2).unwrap();
+        let encoder = FractionalEncoder::new(&params, 2, 20, 20).unwrap();
+        for value in [0.0, 1.0, -1.0, 0.5, -0.75, 3.140625, -123456.0078125].iter() {
+            assert_eq!(encoder.decode(&encoder.encode(*value)), *value);
+        }
+        // Other values are rounded to 20 binary digits after the point
+        for value in [0.1, -2.0 / 3.0, 1234.5678].iter() {
+            let decoded = encoder.decode(&encoder.encode(*value));
+            assert!((decoded - value).abs() <= 2f64.powi(-21));
+        }
+
+        // 2.75 = 2 + 1/2 + 1/4, where X^-1 = -X^63 and X^-2 = -X^62
+        let coefficients = encoder.encode(2.75).poly().val();
+        assert_eq!(coefficients[1], 1);
+        assert_eq!(coefficients[62..], [255, 255]);
+        assert_eq!(coefficients.iter().filter(|c| **c != 0).count(), 3);
+
+        assert_eq!(
+            FractionalEncoder::new(&params, 2, 33, 8).unwrap_err(),
+            ParametersError::InvalidCoefficientCounts {
+                integer_coeff_count: 33,
+                fraction_coeff_count: 8,
+                degree: 64
+            }
+        );
+    }
+
+    #[test]
+    fn fractional_arithmetic_test() {
+        let (degree, t) = (64, 1024);
+        let params = Parameters::new(degree, 1 << 40, t, 3.2).unwrap();
+        let encoder = FractionalEncoder::new(&params, 3, 10, 10).unwrap();
+        let values = [-100.25, -0.5, 0.0, 1.0 / 3.0, 7.875, 2021.1];
+        for a in values.iter() {
+            for b in values.iter() {
+                let (pt_a, pt_b) = (encoder.encode(*a), encoder.encode(*b));
+                // The encoded values, after rounding to 10 digits in base 3
+                let (a, b) = (encoder.decode(&pt_a), encoder.decode(&pt_b));
+
+                let sum = Plaintext::new_from_poly((pt_a.poly() + pt_b.poly()) % (t, degree), t);
+                assert!((encoder.decode(&sum) - (a + b)).abs() < 1e-9);
+                let product =
+                    Plaintext::new_from_poly((pt_a.poly() * pt_b.poly()) % (t, degree), t);
+                assert!((encoder.decode(&product) - a * b).abs() < 1e-9 * (1.0 + (a * b).abs()));
+            }
+        }
+    }
+
+    #[test]
+    fn not_batching_friendly_test() {
+        // 17 is a prime, but not 1 mod 32
+        let params = Parameters::new(16, 1 << 40, 17, 3.2).unwrap();
+        assert_eq!(
+            BatchEncoder::new(&params).unwrap_err(),
+            ParametersError::NotBatchingFriendly { t: 17, degree: 16 }
+        );
+    }
+
+    #[test]
+    fn decode_mismatch_test() {
+        let params = Parameters::new(16, 1 << 40, 257, 3.2).unwrap();
+        let other_t = Plaintext::new(vec![0; 16], 97);
+        let other_degree = Plaintext::new(vec![0; 8], 257);
+        let t_mismatch = Error::PlaintextModulusMismatch {
+            expected: 257,
+            found: 97,
+        };
+        let degree_mismatch = Error::DegreeMismatch {
+            expected: 16,
+            found: 8,
+        };
+
+        let encoder = IntegerEncoder::new(&params, 2).unwrap();
+        assert_eq!(encoder.try_decode(&other_t), Err(t_mismatch));
+        assert_eq!(encoder.try_decode(&other_degree), Err(degree_mismatch));
+        let encoder = FractionalEncoder::new(&params, 2, 4, 4).unwrap();
+        assert_eq!(encoder.try_decode(&other_t), Err(t_mismatch));
+        assert_eq!(encoder.try_decode(&other_degree), Err(degree_mismatch));
+    }
+}
@@ -0,0 +1,79 @@
+use super::params::ParametersError;
+use std::error;
+use std::fmt;
+
+/// The ways in which an operation on keys, plaintexts or ciphertexts can fail.
+/// The `try_` methods return these, where the other methods panic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// The operands were created under different parameters.
+    ParametersMismatch,
+    /// The operands belong to different secret keys.
+    KeyMismatch,
+    /// A plaintext or key has a different degree than the parameters.
+    DegreeMismatch { expected: usize, found: usize },
+    /// A plaintext has a different plaintext modulus than the parameters.
+    PlaintextModulusMismatch { expected: i64, found: i64 },
+    /// The parameters are invalid, or insecure according to their security policy.
+    InvalidParameters(ParametersError),
+    /// The noise of a ciphertext has likely overflowed, so it doesn't decrypt correctly.
+    /// `noise` is the measured invariant noise (see `Ciphertext::invariant_noise`).
+    NoiseOverflow { noise: f64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ParametersMismatch => write!(f, "the operands have different parameters"),
+            Error::KeyMismatch => write!(f, "the operands belong to different keys"),
+            Error::DegreeMismatch { expected, found } => {
+                write!(f, "expected degree {}, found degree {}", expected, found)
+            }
+            Error::PlaintextModulusMismatch { expected, found } => write!(
+                f,
+                "expected plaintext modulus {}, found plaintext modulus {}",
+                expected, found
+            ),
+            Error::InvalidParameters(err) => write!(f, "invalid parameters: {}", err),
+            Error::NoiseOverflow { noise } => {
+                write!(
+                    f,
+                    "the noise has likely overflowed (invariant noise {})",
+                    noise
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<ParametersError> for Error {
+    fn from(err: ParametersError) -> Error {
+        Error::InvalidParameters(err)
+    }
+}
+
+pub(crate) fn check_params<P: PartialEq>(params: &P, other: &P) -> Result<(), Error> {
+    if params == other {
+        Ok(())
+    } else {
+        Err(Error::ParametersMismatch)
+    }
+}
+
+pub(crate) fn check_degree(expected: usize, found: usize) -> Result<(), Error> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(Error::DegreeMismatch { expected, found })
+    }
+}
+
+pub(crate) fn check_key(key_id: u64, other: u64) -> Result<(), Error> {
+    if key_id == other {
+        Ok(())
+    } else {
+        Err(Error::KeyMismatch)
+    }
+}
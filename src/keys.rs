@@ -1,200 +1,788 @@
+use super::big_poly::BigPoly;
+use super::ciphertext::{Ciphertext, CompressedCiphertext};
+use super::error::{self, Error};
+use super::noise;
+use super::params::{Parameters, RnsParameters};
+use super::plaintext::Plaintext;
 use super::poly::Poly;
-use super::random_source;
+use super::random_source::Seeded;
+use super::rns::RnsPoly;
+use super::serialization;
+use num_bigint::BigInt;
 use rand::{CryptoRng, RngCore};
 
 /// A BFV12 Secret Key
 #[derive(Clone, Debug)]
 pub struct SecretKey {
-    ///`s <- R_2`
+    ///`s <- R_2`, or `s <- R_3` for a ternary secret
     pub(crate) poly: Poly,
+    /// The identifier of the key, shared by the keys and ciphertexts derived from it
+    pub(crate) id: u64,
 }
 
 /// A BFV12 Public Key
 ///
 /// * `p_0` = `[-(a * s + e)]_q`
-/// * `p_1` = `a`
-/// * `q` = ciphertext modulus
+/// * `p_1` = `a`, which is stored as a seed if the key was generated with
+///   `SecretKey::public_key_gen_seeded`
+/// * `params` = the parameters the key was generated for
+/// * `id` = the identifier of the secret key
 #[derive(Clone, Debug)]
 pub struct PublicKey {
     pub(crate) p_0: Poly,
-    pub(crate) p_1: Poly,
-    pub(crate) q: i64,
+    pub(crate) p_1: Seeded<Poly>,
+    pub(crate) params: Parameters,
+    pub(crate) id: u64,
 }
 
 /// A BFV12 Relinearization Key, Version 1
 ///
-/// * `val` = `[ ( [-(a_i * s + e_i) + T^i * s^2]_q, a_i) : i \in (0..l)]`
-/// * `T` = the decomposition base used for relinearization
-/// * `l` = `floor(log_t(q))`, the number of levels to decompose
+/// * `val` = `[ ( [-(a_i * s + e_i) + T^i * s^2]_q, a_i) : i \in (0..l)]`, where the a_i are
+///   stored as seeds if the key was generated with `SecretKey::relin_key_gen_1_seeded`
+/// * `l` = `floor(log_T(q))`, the number of levels to decompose
+/// * `params` = the parameters the key was generated for, including the decomposition base T
+/// * `id` = the identifier of the secret key
 #[derive(Clone, Debug)]
 pub struct RelinearizationKey1 {
-    pub(crate) val: Vec<(Poly, Poly)>,
-    pub(crate) base: i64,
+    pub(crate) val: Vec<(Poly, Seeded<Poly>)>,
     pub(crate) l: usize,
+    pub(crate) params: Parameters,
+    pub(crate) id: u64,
 }
 
 /// A BFV12 Relinearization Key, Version 2
 ///
 /// * `rlk_0` = `([-(a * s + e) + p * s^2]_{p*q})`
-/// * `rlk_1` = `a`
+/// * `rlk_1` = `a`, which is stored as a seed if the key was generated with
+///   `SecretKey::relin_key_gen_2_seeded`
 /// * `p` = the amount to scale the modulus, during modulus switching
+/// * `params` = the parameters the key was generated for
+/// * `id` = the identifier of the secret key
+///
+/// Since `p * q` is generally larger than an i64, `rlk_0` and `rlk_1` have big integer coefficients.
 #[derive(Clone, Debug)]
 pub struct RelinearizationKey2 {
-    pub(crate) rlk_0: Poly,
-    pub(crate) rlk_1: Poly,
+    pub(crate) rlk_0: BigPoly,
+    pub(crate) rlk_1: Seeded<BigPoly>,
     pub(crate) p: i64,
+    pub(crate) params: Parameters,
+    pub(crate) id: u64,
+}
+
+impl PublicKey {
+    /// The parameters the key was generated for
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this key was generated from
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// `p_1`, which is regenerated from its seed the first time it is used
+    pub(crate) fn p_1(&self) -> &Poly {
+        self.p_1.get_uniform(self.params.q(), self.params.degree())
+    }
+}
+
+impl RelinearizationKey1 {
+    /// The parameters the key was generated for
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this key was generated from
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The pairs `([-(a_i * s + e_i) + T^i * s^2]_q, a_i)`, where the a_i are regenerated from
+    /// their seeds the first time they are used
+    pub(crate) fn levels(&self) -> impl Iterator<Item = (&Poly, &Poly)> {
+        let (q, degree) = (self.params.q(), self.params.degree());
+        self.val
+            .iter()
+            .map(move |(rlk_i, a_i)| (rlk_i, a_i.get_uniform(q, degree)))
+    }
+}
+
+impl RelinearizationKey2 {
+    /// The parameters the key was generated for
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this key was generated from
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// `rlk_1`, which is regenerated from its seed the first time it is used
+    pub(crate) fn rlk_1(&self) -> &BigPoly {
+        let p_q = BigInt::from(self.p) * self.params.q();
+        self.rlk_1.get_uniform_bigint(&p_q, self.params.degree())
+    }
+}
+
+/// A BFV12 Public Key, with the ciphertext modulus q represented by an RNS basis
+///
+/// * `p_0` = `[-(a * s + e)]_q`
+/// * `p_1` = `a`
+/// * `params` = the parameters the key was generated for
+/// * `id` = the identifier of the secret key
+#[derive(Clone, Debug)]
+pub struct RnsPublicKey {
+    pub(crate) p_0: RnsPoly,
+    pub(crate) p_1: RnsPoly,
+    pub(crate) params: RnsParameters,
+    pub(crate) id: u64,
+}
+
+/// A BFV12 Relinearization Key for a ciphertext modulus represented by an RNS basis
+///
+/// * `val` = `[ ( [-(a_i * s + e_i) + q_i^* * s^2]_q, a_i) : i \in (0..k)]`
+/// * `q_i^*` = `q / q_i`, for each of the k primes q_i in the RNS basis
+/// * `params` = the parameters the key was generated for
+/// * `id` = the identifier of the secret key
+///
+/// This is Relinearization Version 1, using the RNS decomposition in place of the base T
+/// decomposition, so there is one level per prime in the basis.
+#[derive(Clone, Debug)]
+pub struct RnsRelinearizationKey {
+    pub(crate) val: Vec<(RnsPoly, RnsPoly)>,
+    pub(crate) params: RnsParameters,
+    pub(crate) id: u64,
+}
+
+impl RnsPublicKey {
+    /// The parameters the key was generated for
+    pub fn params(&self) -> &RnsParameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this key was generated from
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl RnsRelinearizationKey {
+    /// The parameters the key was generated for
+    pub fn params(&self) -> &RnsParameters {
+        &self.params
+    }
+
+    /// The identifier of the secret key this key was generated from
+    pub fn id(&self) -> u64 {
+        self.id
+    }
 }
 
 impl SecretKey {
-    /// Generate a secret key by sampling the coefficients of s uniformly
-    /// from R_2, which in this implementation is the set {0, 1}.
+    /// Generate a secret key by sampling the coefficients of s from the secret distribution of
+    /// the parameters: by default uniformly from R_2, which in this implementation is the set {0, 1}.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey};
     ///
-    /// * `degree`: the polynomial degree of the secret key
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// ```
+    pub fn generate<T: RngCore + CryptoRng>(params: &Parameters, rng: &mut T) -> SecretKey {
+        SecretKey::from_poly(params.secret_distribution().sample(params.degree(), rng))
+    }
+
+    /// Generate a secret key for a ciphertext modulus represented by an RNS basis, by sampling
+    /// the coefficients of s from the secret distribution of the parameters, as in `generate`.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
     /// * `rng`: the RNG used to generate randomness
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use bfv12::{RnsBasis, RnsParameters, SecretKey};
     ///
-    /// let degree = 4;
-    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let basis = RnsBasis::generate(4, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate_rns(&params, &mut rng);
     /// ```
-    pub fn generate<T: RngCore + CryptoRng>(degree: usize, rng: &mut T) -> SecretKey {
-        SecretKey {
-            poly: random_source::get_uniform(2, degree, rng),
-        }
+    pub fn generate_rns<T: RngCore + CryptoRng>(params: &RnsParameters, rng: &mut T) -> SecretKey {
+        SecretKey::from_poly(params.secret_distribution().sample(params.degree(), rng))
+    }
+
+    // The identifier is a hash of s. It reveals nothing that the public key doesn't, since
+    // the public key can already be used to check a guess for s.
+    pub(crate) fn from_poly(poly: Poly) -> SecretKey {
+        let id = serialization::secret_key_hash(&poly);
+        SecretKey { poly, id }
+    }
+
+    /// An identifier of the key, which the keys and ciphertexts derived from it share.
+    /// Ciphertexts are checked to belong to the same key when they are combined or decrypted.
+    pub fn id(&self) -> u64 {
+        self.id
     }
 
     /// Generate a public key from a secret key.
     ///
-    /// * `q`: the ciphertext modulus
-    /// * `std_dev`: the standard deviation for error generation
+    /// The security of the parameters is checked first, according to their security policy
+    /// (see `Parameters::with_security_policy`): insecure parameters are rejected with a panic.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
     /// * `rng`: the RNG used to generate randomness
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use bfv12::{Parameters, SecretKey};
     ///
-    /// let degree = 4;
-    /// let std_dev = 3.2;
-    /// let q = 65536;
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
     ///
-    /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
     /// ```
     pub fn public_key_gen<T: RngCore + CryptoRng>(
         &self,
-        q: i64,
-        std_dev: f64,
+        params: &Parameters,
         rng: &mut T,
     ) -> PublicKey {
+        self.try_public_key_gen(params, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Generate a public key from a secret key as in `public_key_gen`, returning an error instead
+    /// of panicking if the key doesn't match the parameters, or if the parameters are rejected by
+    /// their security policy.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Parameters, ParametersError, SecretKey, SecurityPolicy};
+    ///
+    /// let params = Parameters::new(4, 65536, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_security_policy(SecurityPolicy::Reject { min_bits: 128 });
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let err = secret_key.try_public_key_gen(&params, &mut rng).unwrap_err();
+    /// assert!(matches!(err, Error::InvalidParameters(ParametersError::Insecure { .. })));
+    /// ```
+    pub fn try_public_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> Result<PublicKey, Error> {
+        self.public_key_gen_with(params, false, rng)
+    }
+
+    /// Generate a public key from a secret key, where the uniformly random `p_1` is sampled from
+    /// a seed, and regenerated from it when it is used. Only the seed is serialized, which
+    /// halves the size of the key.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(1024, 1 << 40, 12, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let seeded_public_key = secret_key.public_key_gen_seeded(&params, &mut rng);
+    /// assert!(seeded_public_key.to_bytes().len() < public_key.to_bytes().len() / 2 + 64);
+    /// ```
+    pub fn public_key_gen_seeded<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> PublicKey {
+        self.public_key_gen_with(params, true, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn public_key_gen_with<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        seeded: bool,
+        rng: &mut T,
+    ) -> Result<PublicKey, Error> {
         let s = self.poly.clone();
-        let degree = s.degree();
+        let (degree, q) = (params.degree(), params.q());
+        error::check_degree(degree, s.degree())?;
+        params.try_enforce_security_policy()?;
 
-        let a = random_source::get_uniform(q, degree, rng);
-        let e = random_source::get_gaussian(std_dev, degree, rng);
-        let p_1 = a.clone();
-        let p_0 = (-(a.clone() * s.clone() + e)) % (q, degree);
+        let p_1 = Seeded::uniform(q, degree, seeded, rng);
+        let a = p_1.get_uniform(q, degree);
+        let e = params.error_distribution().sample(degree, rng);
+        let p_0 = (-(params.mul_mod(a, &s) + e)) % (q, degree);
 
-        PublicKey { p_0, p_1, q }
+        Ok(PublicKey {
+            p_0,
+            p_1,
+            params: params.clone(),
+            id: self.id,
+        })
     }
 
-    /// Generate a relinearization key, using the approach in Version 1
+    /// Encrypt a plaintext with the secret key, as `([-(a * s + e) + delta * m]_q, a)` for a
+    /// uniformly random a. The error is smaller than with public key encryption, which adds the
+    /// error of the public key multiplied by u.
+    /// The plaintext must have the plaintext modulus and degree of the parameters.
     ///
-    /// * `q`: the ciphertext modulus
-    /// * `std_dev`: the standard deviation for error generation
+    /// * `plaintext`: the plaintext to encrypt
+    /// * `params`: the parameters for this instantiation of B/FV
     /// * `rng`: the RNG used to generate randomness
-    /// * `base`: the decomposition base used for relinearization
     ///
-    /// Note on base selection:
-    /// The base can be chosen to trade off relinearisation time and space, for error accumulation.
-    /// The larger the base, the larger the error. The bounds on the base are discussed in the paper.
-    /// Choosing T = ceil(sqrt(q)) will minimize relinearisation time and space, at the expense of error.
-    /// Choosing T = log_2(q) will decrease error at the cost of relinearisation time and space.
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, Plaintext, SecretKey};
+    ///
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], params.t());
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let ct = secret_key.encrypt(&pt, &params, &mut rng);
+    /// assert_eq!(ct.decrypt(&secret_key), pt);
+    /// ```
+    pub fn encrypt<T: RngCore + CryptoRng>(
+        &self,
+        plaintext: &Plaintext,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> Ciphertext {
+        self.try_encrypt(plaintext, params, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Encrypt a plaintext with the secret key as in `encrypt`, returning an error instead of
+    /// panicking if the plaintext or the key don't match the parameters.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Parameters, Plaintext, SecretKey};
+    ///
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], 3);
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let err = secret_key.try_encrypt(&pt, &params, &mut rng).unwrap_err();
+    /// assert_eq!(err, Error::PlaintextModulusMismatch { expected: 4, found: 3 });
+    /// ```
+    pub fn try_encrypt<T: RngCore + CryptoRng>(
+        &self,
+        plaintext: &Plaintext,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> Result<Ciphertext, Error> {
+        let (c_0, a) = self.encrypt_with(plaintext, params, false, rng)?;
+        let c_1 = a.get_uniform(params.q(), params.degree()).clone();
+
+        Ok(Ciphertext {
+            c_0,
+            c_1,
+            params: params.clone(),
+            key_id: self.id,
+            noise_bound: noise::fresh_secret(params),
+        })
+    }
+
+    /// Encrypt a plaintext with the secret key as in `encrypt`, but sample a from a fresh seed
+    /// and keep only the seed. The compressed ciphertext is about half the size when serialized,
+    /// and is expanded to a regular ciphertext with `CompressedCiphertext::expand`.
+    ///
+    /// * `plaintext`: the plaintext to encrypt
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, Plaintext, SecretKey};
+    ///
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], params.t());
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let compressed = secret_key.encrypt_compressed(&pt, &params, &mut rng);
+    /// assert_eq!(compressed.expand().decrypt(&secret_key), pt);
+    /// ```
+    pub fn encrypt_compressed<T: RngCore + CryptoRng>(
+        &self,
+        plaintext: &Plaintext,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> CompressedCiphertext {
+        self.try_encrypt_compressed(plaintext, params, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Encrypt a plaintext with the secret key as in `encrypt_compressed`, returning an error
+    /// instead of panicking as in `try_encrypt`.
+    pub fn try_encrypt_compressed<T: RngCore + CryptoRng>(
+        &self,
+        plaintext: &Plaintext,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> Result<CompressedCiphertext, Error> {
+        let (c_0, c_1) = self.encrypt_with(plaintext, params, true, rng)?;
+
+        Ok(CompressedCiphertext {
+            c_0,
+            c_1,
+            params: params.clone(),
+            key_id: self.id,
+        })
+    }
+
+    fn encrypt_with<T: RngCore + CryptoRng>(
+        &self,
+        plaintext: &Plaintext,
+        params: &Parameters,
+        seeded: bool,
+        rng: &mut T,
+    ) -> Result<(Poly, Seeded<Poly>), Error> {
+        let s = &self.poly;
+        let (degree, q) = (params.degree(), params.q());
+        error::check_degree(degree, s.degree())?;
+        plaintext.check(params)?;
+        let m = plaintext.poly() % (params.t(), degree);
+
+        let a = Seeded::uniform(q, degree, seeded, rng);
+        let e = params.error_distribution().sample(degree, rng);
+        let c_0 =
+            (-(params.mul_mod(a.get_uniform(q, degree), s) + e) + m * params.delta()) % (q, degree);
+
+        Ok((c_0, a))
+    }
+
+    /// Generate a relinearization key, using the approach in Version 1.
+    /// The decomposition base T is `params.rlk_base()`, see `Parameters::with_rlk_base`.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use bfv12::{Parameters, SecretKey};
     ///
-    /// let degree = 4;
-    /// let std_dev = 3.2;
-    /// let q = 65536;
-    /// let rlk_base = (q as f64).log2() as i64;
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
     ///
-    /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let relin_key_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base);
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let relin_key_1 = secret_key.relin_key_gen_1(&params, &mut rng);
     /// ```
     pub fn relin_key_gen_1<T: RngCore + CryptoRng>(
         &self,
-        q: i64,
-        std_dev: f64,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> RelinearizationKey1 {
+        self.try_relin_key_gen_1(params, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Generate a relinearization key as in `relin_key_gen_1`, returning an error instead of
+    /// panicking as in `try_public_key_gen`.
+    pub fn try_relin_key_gen_1<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        rng: &mut T,
+    ) -> Result<RelinearizationKey1, Error> {
+        self.relin_key_gen_1_with(params, false, rng)
+    }
+
+    /// Generate a relinearization key, using the approach in Version 1, where the uniformly
+    /// random a_i are sampled from seeds, and regenerated from them when they are used.
+    /// Only the seeds are serialized, which halves the size of the key.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let relin_key_1 = secret_key.relin_key_gen_1_seeded(&params, &mut rng);
+    /// ```
+    pub fn relin_key_gen_1_seeded<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
         rng: &mut T,
-        base: i64,
     ) -> RelinearizationKey1 {
-        let degree = self.poly.degree();
+        self.relin_key_gen_1_with(params, true, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn relin_key_gen_1_with<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        seeded: bool,
+        rng: &mut T,
+    ) -> Result<RelinearizationKey1, Error> {
         let s = self.poly.clone();
+        let (degree, q, base) = (params.degree(), params.q(), params.rlk_base());
+        error::check_degree(degree, s.degree())?;
+        params.try_enforce_security_policy()?;
         // l is the number of levels to decompose s^2 and c_2 into.
         // l is a function of base (T in the paper): l = floor(log_T(q)).
-        let l = (q as f64).log(base as f64).floor() as usize;
+        let l = params.rlk_levels();
 
         let val = (0..l)
             .map(|i| {
-                let a_i = random_source::get_uniform(q, degree, rng);
-                let e_i = random_source::get_gaussian(std_dev, degree, rng);
+                let a_i = Seeded::uniform(q, degree, seeded, rng);
+                let e_i = params.error_distribution().sample(degree, rng);
                 let base_i = base.pow(i as u32);
-                let rlk_i_raw = -(a_i.clone() * s.clone() + e_i) + s.clone() * s.clone() * base_i;
+                let rlk_i_raw = -(params.mul_mod(a_i.get_uniform(q, degree), &s) + e_i)
+                    + params.mul_mod(&s, &(s.clone() * base_i));
                 let rlk_i = rlk_i_raw % (q, degree);
                 (rlk_i, a_i)
             })
             .collect();
-        RelinearizationKey1 { val, base, l }
+        Ok(RelinearizationKey1 {
+            val,
+            l,
+            params: params.clone(),
+            id: self.id,
+        })
     }
 
     /// Generate a relinearization key, using the approach in Version 2
-    /// * `q`: the ciphertext modulus
-    /// * `std_dev`: the standard deviation for error generation
-    /// * `rng`: the RNG used to generate randomness
+    /// * `params`: the parameters for this instantiation of B/FV
     /// * `p`: the amount to scale the modulus, during modulus switching
+    /// * `rng`: the RNG used to generate randomness
     ///
     /// Note on p selection:
-    /// Technically p needs to be >= q^3 for security (see paper discussion on Relinearization Version 2),
-    /// However, setting p = q^3 results in an overflow when taking p * q.
-    /// Therefore, in this library we will test with a smaller p, and recommend using Relinearization Version 1.
+    /// p needs to be >= q^3 for security (see paper discussion on Relinearization Version 2).
+    /// The key is computed mod p * q with big integers, so p * q doesn't need to fit in an i64,
+    /// but p itself does.
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use bfv12::{Parameters, SecretKey};
     ///
-    /// let degree = 4;
-    /// let std_dev = 3.2;
-    /// let q = 65536;
-    /// let p = 2_i64.pow(13) * q;
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+    /// let p = params.q() * params.q() * params.q();
     ///
-    /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let relin_key_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p);
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let relin_key_2 = secret_key.relin_key_gen_2(&params, p, &mut rng);
     /// ```
     pub fn relin_key_gen_2<T: RngCore + CryptoRng>(
         &self,
-        q: i64,
-        std_dev: f64,
+        params: &Parameters,
+        p: i64,
         rng: &mut T,
+    ) -> RelinearizationKey2 {
+        self.try_relin_key_gen_2(params, p, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Generate a relinearization key as in `relin_key_gen_2`, returning an error instead of
+    /// panicking as in `try_public_key_gen`.
+    pub fn try_relin_key_gen_2<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
         p: i64,
+        rng: &mut T,
+    ) -> Result<RelinearizationKey2, Error> {
+        self.relin_key_gen_2_with(params, p, false, rng)
+    }
+
+    /// Generate a relinearization key, using the approach in Version 2, where the uniformly
+    /// random a is sampled from a seed, and regenerated from it when it is used.
+    /// Only the seed is serialized, which halves the size of the key.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `p`: the amount to scale the modulus, during modulus switching
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+    /// let p = params.q() * params.q() * params.q();
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let relin_key_2 = secret_key.relin_key_gen_2_seeded(&params, p, &mut rng);
+    /// ```
+    pub fn relin_key_gen_2_seeded<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        p: i64,
+        rng: &mut T,
     ) -> RelinearizationKey2 {
-        let degree = self.poly.degree();
-        let s = self.poly.clone();
+        self.relin_key_gen_2_with(params, p, true, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn relin_key_gen_2_with<T: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        p: i64,
+        seeded: bool,
+        rng: &mut T,
+    ) -> Result<RelinearizationKey2, Error> {
+        let degree = params.degree();
+        error::check_degree(degree, self.poly.degree())?;
+        params.try_enforce_security_policy()?;
+        let s = BigPoly::from(&self.poly);
+        let p_q = BigInt::from(p) * params.q();
+
+        let rlk_1 = Seeded::uniform_bigint(&p_q, degree, seeded, rng);
+        let a = rlk_1.get_uniform_bigint(&p_q, degree).clone();
+        let e = BigPoly::from(&params.error_distribution().sample(degree, rng));
+        let s_2_p = s.clone() * s.clone() * BigPoly::new(vec![BigInt::from(p)]);
+        let rlk_0 = (-(a * s + e) + s_2_p) % (&p_q, degree);
 
-        let a = random_source::get_uniform(p * q, degree, rng);
-        let e = random_source::get_gaussian(std_dev, degree, rng);
-        let rlk_0 = (-(a.clone() * s.clone() + e) + s.clone() * s.clone() * p) % (p * q, degree);
+        Ok(RelinearizationKey2 {
+            rlk_0,
+            rlk_1,
+            p,
+            params: params.clone(),
+            id: self.id,
+        })
+    }
 
-        RelinearizationKey2 { rlk_0, rlk_1: a, p }
+    /// Generate a public key for a ciphertext modulus represented by an RNS basis.
+    ///
+    /// The security of the parameters is checked first, according to their security policy,
+    /// as in `public_key_gen`.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{RnsBasis, RnsParameters, SecretKey};
+    ///
+    /// let basis = RnsBasis::generate(4, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate_rns(&params, &mut rng);
+    /// let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+    /// ```
+    pub fn rns_public_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        params: &RnsParameters,
+        rng: &mut T,
+    ) -> RnsPublicKey {
+        self.try_rns_public_key_gen(params, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Generate a public key as in `rns_public_key_gen`, returning an error instead of panicking
+    /// as in `try_public_key_gen`.
+    pub fn try_rns_public_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        params: &RnsParameters,
+        rng: &mut T,
+    ) -> Result<RnsPublicKey, Error> {
+        let basis = params.basis();
+        error::check_degree(params.degree(), self.poly.degree())?;
+        params.try_enforce_security_policy()?;
+        let s = RnsPoly::from_poly(&self.poly, basis);
+
+        let a = RnsPoly::uniform(basis, rng);
+        let e = RnsPoly::from_poly(
+            &params.error_distribution().sample(params.degree(), rng),
+            basis,
+        );
+        let p_0 = -(a.clone() * s + e);
+
+        Ok(RnsPublicKey {
+            p_0,
+            p_1: a,
+            params: params.clone(),
+            id: self.id,
+        })
+    }
+
+    /// Generate a relinearization key for a ciphertext modulus represented by an RNS basis.
+    ///
+    /// * `params`: the parameters for this instantiation of B/FV
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{RnsBasis, RnsParameters, SecretKey};
+    ///
+    /// let basis = RnsBasis::generate(4, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    ///
+    /// let secret_key = SecretKey::generate_rns(&params, &mut rng);
+    /// let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+    /// ```
+    pub fn rns_relin_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        params: &RnsParameters,
+        rng: &mut T,
+    ) -> RnsRelinearizationKey {
+        self.try_rns_relin_key_gen(params, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Generate a relinearization key as in `rns_relin_key_gen`, returning an error instead of
+    /// panicking as in `try_public_key_gen`.
+    pub fn try_rns_relin_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        params: &RnsParameters,
+        rng: &mut T,
+    ) -> Result<RnsRelinearizationKey, Error> {
+        let basis = params.basis();
+        error::check_degree(params.degree(), self.poly.degree())?;
+        params.try_enforce_security_policy()?;
+        let s = RnsPoly::from_poly(&self.poly, basis);
+        let s_2 = s.clone() * s.clone();
+
+        let val = RnsPoly::gadget(basis)
+            .iter()
+            .map(|q_star_i| {
+                let a_i = RnsPoly::uniform(basis, rng);
+                let e_i = RnsPoly::from_poly(
+                    &params.error_distribution().sample(params.degree(), rng),
+                    basis,
+                );
+                let rlk_i = -(a_i.clone() * s.clone() + e_i) + s_2.mul_bigint(q_star_i);
+                (rlk_i, a_i)
+            })
+            .collect();
+        Ok(RnsRelinearizationKey {
+            val,
+            params: params.clone(),
+            id: self.id,
+        })
     }
 }
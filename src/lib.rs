@@ -1,12 +1,33 @@
 #![doc = include_str!("../README.md")]
 
+mod armor;
+mod big_poly;
 mod ciphertext;
+mod encoder;
+mod error;
 mod keys;
+mod noise;
+mod ntt;
+mod params;
 mod plaintext;
 mod poly;
 mod random_source;
+mod rns;
+mod security;
+mod serialization;
+#[allow(clippy::module_inception)]
 mod tests;
 
-pub use ciphertext::Ciphertext;
-pub use keys::{PublicKey, RelinearizationKey1, RelinearizationKey2, SecretKey};
+pub use ciphertext::{Ciphertext, CompressedCiphertext, RnsCiphertext};
+pub use encoder::{BatchEncoder, FractionalEncoder, IntegerEncoder};
+pub use error::Error;
+pub use keys::{
+    PublicKey, RelinearizationKey1, RelinearizationKey2, RnsPublicKey, RnsRelinearizationKey,
+    SecretKey,
+};
+pub use params::{Parameters, ParametersError, RnsParameters, SecurityLevel};
 pub use plaintext::Plaintext;
+pub use random_source::{ErrorDistribution, SecretDistribution};
+pub use rns::RnsBasis;
+pub use security::{estimate_security, SecurityEstimate, SecurityPolicy};
+pub use serialization::SerializationError;
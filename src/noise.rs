@@ -0,0 +1,90 @@
+use super::params::Parameters;
+
+// Upper bounds on the infinity norm of the invariant noise v of a ciphertext, as in SEAL: the
+// polynomial with `(t / q) * (c_0 + c_1 * s) = m + v + t * r` for some polynomial r. A ciphertext
+// decrypts correctly while `||v|| < 1/2`.
+//
+// The bounds are worst-case, using ||a * b|| <= N * ||a|| * ||b|| in R, the tail cut B of the
+// error distribution, and the largest numbers h and h_u of nonzero coefficients of s and u. They
+// hold for every ciphertext, and need no secret key, but are typically a few bits per operation
+// above the noise measured with `Ciphertext::invariant_noise`.
+
+// B and h
+fn error_bound_and_weight(params: &Parameters) -> (f64, f64) {
+    let degree = params.degree();
+    let weight = params.secret_distribution().hamming_weight(degree);
+    (params.error_distribution().bound(), weight as f64)
+}
+
+// The noise of encoding m in [0, t) as delta * m, since t * delta = q - r_t(q): r_t(q) * ||m|| / q
+fn encoding(params: &Parameters) -> f64 {
+    let (q, t) = (params.q(), params.t());
+    ((q % t) * (t - 1)) as f64 / q as f64
+}
+
+// Public key encryption: c_0 + c_1 * s = delta * m + e_1 + e_2 * s - e * u, so the error is at
+// most B * (1 + h + h_u). u is dense even when s is sparse.
+pub(crate) fn fresh_public(params: &Parameters) -> f64 {
+    let (bound, weight) = error_bound_and_weight(params);
+    let randomness = params.secret_distribution().randomness();
+    let randomness_weight = randomness.hamming_weight(params.degree()) as f64;
+    let t = params.t() as f64;
+    t * bound * (1.0 + weight + randomness_weight) / params.q() as f64 + encoding(params)
+}
+
+// Secret key encryption: c_0 + c_1 * s = delta * m - e, so the error is at most B.
+pub(crate) fn fresh_secret(params: &Parameters) -> f64 {
+    let (bound, _) = error_bound_and_weight(params);
+    let t = params.t() as f64;
+    t * bound / params.q() as f64 + encoding(params)
+}
+
+// Addition of a plaintext m', encoded as delta * m', adds the noise of its encoding.
+pub(crate) fn add_plain(params: &Parameters, noise: f64) -> f64 {
+    noise + encoding(params)
+}
+
+// Multiplication by a plaintext m', lifted to (-t/2, t/2]: the noise is v * m'.
+pub(crate) fn mul_plain(params: &Parameters, noise: f64) -> f64 {
+    params.degree() as f64 * (params.t() as f64 / 2.0) * noise
+}
+
+// Multiplication before relinearization. With x_i = c_0 + c_1 * s for the coefficients of the
+// ciphertexts in [0, q), ||(t / q) * x_i|| <= t * (1 + h), and the noise is
+// `v_1 * (t / q) * x_2 + v_2 * (t / q) * x_1 - v_1 * v_2`, plus the rounding of the three
+// components after scaling, evaluated at (1, s, s^2).
+pub(crate) fn mul(params: &Parameters, noise_1: f64, noise_2: f64) -> f64 {
+    let (_, weight) = error_bound_and_weight(params);
+    let (degree, t, q) = (params.degree() as f64, params.t() as f64, params.q() as f64);
+    degree * t * (1.0 + weight) * (noise_1 + noise_2)
+        + degree * noise_1 * noise_2
+        + t * (1.0 + weight + weight * weight) / (2.0 * q)
+}
+
+// Relinearization Version 1 adds the sum of the decomposed c_2^(i) * e_i. The lower l - 1 digits
+// are below T, and the top digit is below q / T^(l - 1).
+pub(crate) fn relinearization_1(params: &Parameters) -> f64 {
+    let (bound, _) = error_bound_and_weight(params);
+    let (degree, t, q) = (params.degree() as f64, params.t() as f64, params.q() as f64);
+    let (base, levels) = (params.rlk_base() as f64, params.rlk_levels() as i32);
+    let digits = (levels - 1) as f64 * (base - 1.0) + q / base.powi(levels - 1);
+    t * degree * digits * bound / q
+}
+
+// Relinearization Version 2 adds c_2 * e / p, with c_2 in [0, q), and the rounding of the two
+// components after dividing by p, evaluated at (1, s).
+pub(crate) fn relinearization_2(params: &Parameters, p: i64) -> f64 {
+    let (bound, weight) = error_bound_and_weight(params);
+    let (degree, t, q) = (params.degree() as f64, params.t() as f64, params.q() as f64);
+    t * degree * bound / p as f64 + t * (1.0 + weight) / (2.0 * q)
+}
+
+// The number of bits by which noise below `noise` can grow before reaching 1/2.
+pub(crate) fn budget(noise: f64) -> u32 {
+    let bits = -(2.0 * noise).log2();
+    if bits >= 1.0 {
+        bits.floor() as u32
+    } else {
+        0
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Precomputed tables for the negacyclic Number Theoretic Transform over
+/// `R_q = Z_q[X] / (X^N + 1)`.
+///
+/// The negacyclic NTT exists when q is a prime with `q = 1 mod 2N`, in which case
+/// there is a primitive 2N-th root of unity psi in Z_q. The tables store the powers
+/// of psi (and its inverse) in bit-reversed order, as used by the Cooley-Tukey
+/// forward transform and the Gentleman-Sande inverse transform.
+#[derive(Debug)]
+pub(crate) struct NttTables {
+    q: i64,
+    degree: usize,
+    psi_rev: Vec<i64>,
+    psi_inv_rev: Vec<i64>,
+    degree_inv: i64,
+}
+
+type TableCache = Mutex<HashMap<(i64, usize), Option<Arc<NttTables>>>>;
+
+fn table_cache() -> &'static TableCache {
+    static CACHE: OnceLock<TableCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn aux_prime_cache() -> &'static Mutex<HashMap<usize, i64>> {
+    static CACHE: OnceLock<Mutex<HashMap<usize, i64>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+impl NttTables {
+    /// Compute the tables for (q, degree), or return `None` if q is not NTT-friendly.
+    pub(crate) fn new(q: i64, degree: usize) -> Option<NttTables> {
+        if !is_ntt_friendly(q, degree) {
+            return None;
+        }
+        let psi = primitive_root(q, 2 * degree as i64)?;
+        let psi_inv = inv_mod(psi, q);
+        let log_degree = degree.trailing_zeros();
+
+        let mut psi_rev = vec![0; degree];
+        let mut psi_inv_rev = vec![0; degree];
+        let (mut psi_i, mut psi_inv_i) = (1, 1);
+        for i in 0..degree {
+            let rev_i = bit_reverse(i, log_degree);
+            psi_rev[rev_i] = psi_i;
+            psi_inv_rev[rev_i] = psi_inv_i;
+            psi_i = mul_mod(psi_i, psi, q);
+            psi_inv_i = mul_mod(psi_inv_i, psi_inv, q);
+        }
+
+        Some(NttTables {
+            q,
+            degree,
+            psi_rev,
+            psi_inv_rev,
+            degree_inv: inv_mod(degree as i64, q),
+        })
+    }
+
+    /// Get the (cached) tables for (q, degree), or `None` if q is not NTT-friendly.
+    /// The tables are computed once per (q, degree) pair and shared afterwards.
+    pub(crate) fn get(q: i64, degree: usize) -> Option<Arc<NttTables>> {
+        let mut cache = table_cache().lock().unwrap();
+        cache
+            .entry((q, degree))
+            .or_insert_with(|| NttTables::new(q, degree).map(Arc::new))
+            .clone()
+    }
+
+    /// Get the tables for the auxiliary prime used for exact integer products in degree `degree`.
+    /// The auxiliary prime is the largest NTT-friendly prime below 2^62.
+    pub(crate) fn aux(degree: usize) -> Option<Arc<NttTables>> {
+        let prime = *aux_prime_cache()
+            .lock()
+            .unwrap()
+            .entry(degree)
+            .or_insert_with(|| find_ntt_prime(62, degree).unwrap_or(0));
+        NttTables::get(prime, degree)
+    }
+
+    pub(crate) fn modulus(&self) -> i64 {
+        self.q
+    }
+
+    /// Transform coefficients in [0, q) into the NTT (evaluation) domain, in place.
+    /// The output is in bit-reversed order.
+    pub(crate) fn forward(&self, a: &mut [i64]) {
+        assert_eq!(a.len(), self.degree);
+        let q = self.q;
+        let mut t = self.degree;
+        let mut m = 1;
+        while m < self.degree {
+            t /= 2;
+            for i in 0..m {
+                let j_1 = 2 * i * t;
+                let s = self.psi_rev[m + i];
+                for j in j_1..(j_1 + t) {
+                    let u = a[j];
+                    let v = mul_mod(a[j + t], s, q);
+                    a[j] = add_mod(u, v, q);
+                    a[j + t] = sub_mod(u, v, q);
+                }
+            }
+            m *= 2;
+        }
+    }
+
+    /// Transform values in the NTT domain back to coefficients in [0, q), in place.
+    pub(crate) fn inverse(&self, a: &mut [i64]) {
+        assert_eq!(a.len(), self.degree);
+        let q = self.q;
+        let mut t = 1;
+        let mut m = self.degree;
+        while m > 1 {
+            let h = m / 2;
+            let mut j_1 = 0;
+            for i in 0..h {
+                let s = self.psi_inv_rev[h + i];
+                for j in j_1..(j_1 + t) {
+                    let u = a[j];
+                    let v = a[j + t];
+                    a[j] = add_mod(u, v, q);
+                    a[j + t] = mul_mod(sub_mod(u, v, q), s, q);
+                }
+                j_1 += 2 * t;
+            }
+            t *= 2;
+            m = h;
+        }
+        for a_j in a.iter_mut() {
+            *a_j = mul_mod(*a_j, self.degree_inv, q);
+        }
+    }
+
+    /// Multiply two polynomials with coefficients in [0, q) and length N in R_q.
+    pub(crate) fn negacyclic_mul(&self, a: &[i64], b: &[i64]) -> Vec<i64> {
+        let mut a_ntt = a.to_vec();
+        let mut b_ntt = b.to_vec();
+        self.forward(&mut a_ntt);
+        self.forward(&mut b_ntt);
+        for (a_j, b_j) in a_ntt.iter_mut().zip(b_ntt.iter()) {
+            *a_j = mul_mod(*a_j, *b_j, self.q);
+        }
+        self.inverse(&mut a_ntt);
+        a_ntt
+    }
+}
+
+/// Returns true if q is a prime with `q = 1 mod 2 * degree`, and degree is a power of two,
+/// so that the negacyclic NTT of length `degree` exists over Z_q.
+pub(crate) fn is_ntt_friendly(q: i64, degree: usize) -> bool {
+    degree.is_power_of_two() && q > 2 && (q - 1) % (2 * degree as i64) == 0 && is_prime(q)
+}
+
+/// Find the largest NTT-friendly prime for `degree` that is smaller than 2^bits.
+pub(crate) fn find_ntt_prime(bits: u32, degree: usize) -> Option<i64> {
+    previous_ntt_prime(1 << bits, degree)
+}
+
+/// Find the largest NTT-friendly prime for `degree` that is smaller than `upper`.
+pub(crate) fn previous_ntt_prime(upper: i64, degree: usize) -> Option<i64> {
+    let step = 2 * degree as i64;
+    // The largest candidate below `upper` that is 1 mod 2N.
+    let mut candidate = (upper - 2) / step * step + 1;
+    while candidate > step {
+        if is_prime(candidate) {
+            return Some(candidate);
+        }
+        candidate -= step;
+    }
+    None
+}
+
+// Find an element of exact order `order` (a power of two) in Z_q^*.
+fn primitive_root(q: i64, order: i64) -> Option<i64> {
+    let exponent = (q - 1) / order;
+    (2..q).map(|x| pow_mod(x, exponent, q)).find(|root| {
+        // Since the order is a power of two, root has exact order `order`
+        // if and only if root^(order / 2) = -1.
+        pow_mod(*root, order / 2, q) == q - 1
+    })
+}
+
+fn bit_reverse(i: usize, bits: u32) -> usize {
+    if bits == 0 {
+        0
+    } else {
+        i.reverse_bits() >> (usize::BITS - bits)
+    }
+}
+
+pub(crate) fn add_mod(a: i64, b: i64, q: i64) -> i64 {
+    ((a as i128 + b as i128) % q as i128) as i64
+}
+
+pub(crate) fn sub_mod(a: i64, b: i64, q: i64) -> i64 {
+    ((a as i128 - b as i128).rem_euclid(q as i128)) as i64
+}
+
+pub(crate) fn mul_mod(a: i64, b: i64, q: i64) -> i64 {
+    ((a as i128 * b as i128).rem_euclid(q as i128)) as i64
+}
+
+pub(crate) fn pow_mod(base: i64, exponent: i64, q: i64) -> i64 {
+    let mut result = 1 % q;
+    let mut base = base.rem_euclid(q);
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, q);
+        }
+        base = mul_mod(base, base, q);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Modular inverse of a modulo a prime q, using Fermat's little theorem.
+pub(crate) fn inv_mod(a: i64, q: i64) -> i64 {
+    pow_mod(a, q - 2, q)
+}
+
+/// Deterministic Miller-Rabin primality test, exact for all 64-bit inputs.
+pub(crate) fn is_prime(n: i64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    const WITNESSES: [i64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for p in WITNESSES.iter() {
+        if n % p == 0 {
+            return n == *p;
+        }
+    }
+
+    let mut d = n - 1;
+    let mut r = 0;
+    while d % 2 == 0 {
+        d /= 2;
+        r += 1;
+    }
+
+    'witness: for a in WITNESSES.iter() {
+        let mut x = pow_mod(*a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..r {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_prime_test() {
+        let primes = vec![2, 3, 17, 12289, 65537, 4611686018427322369];
+        for p in primes.into_iter() {
+            assert!(is_prime(p), "{} should be prime", p);
+        }
+        let composites = vec![0, 1, 4, 65536, 3215031751, 4611686018427322371];
+        for c in composites.into_iter() {
+            assert!(!is_prime(c), "{} should not be prime", c);
+        }
+    }
+
+    #[test]
+    fn find_ntt_prime_test() {
+        assert_eq!(find_ntt_prime(14, 1024), Some(12289));
+        let p = find_ntt_prime(62, 4096).unwrap();
+        assert!(p < 1 << 62);
+        assert!(is_ntt_friendly(p, 4096));
+    }
+
+    #[test]
+    fn ntt_roundtrip_test() {
+        let q = 12289;
+        let degree = 16;
+        let tables = NttTables::new(q, degree).unwrap();
+        let a: Vec<i64> = (0..degree as i64).map(|i| (i * 7919) % q).collect();
+        let mut a_ntt = a.clone();
+        tables.forward(&mut a_ntt);
+        assert_ne!(a_ntt, a);
+        tables.inverse(&mut a_ntt);
+        assert_eq!(a_ntt, a);
+    }
+
+    #[test]
+    fn not_ntt_friendly_test() {
+        // Not prime
+        assert!(NttTables::new(65536, 4).is_none());
+        // Prime, but not 1 mod 2N
+        assert!(NttTables::new(12289, 8192).is_none());
+        // Degree not a power of two
+        assert!(NttTables::new(12289, 12).is_none());
+    }
+}
@@ -0,0 +1,1033 @@
+use super::ntt::{self, NttTables};
+use super::poly::Poly;
+use super::random_source::{ErrorDistribution, SecretDistribution};
+use super::rns::RnsBasis;
+use super::security::{self, SecurityEstimate, SecurityPolicy};
+use super::serialization;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// The largest supported ciphertext modulus is 2^62, so that the sum of two
+/// coefficients in [0, q) can't overflow an i64.
+pub(crate) const MAX_Q_BITS: u32 = 62;
+
+/// The polynomial degrees covered by the HomomorphicEncryption.org security standard.
+const STANDARD_DEGREES: [usize; 6] = [1024, 2048, 4096, 8192, 16384, 32768];
+
+/// The largest ciphertext modulus, in bits, for each of `STANDARD_DEGREES` at each security level,
+/// from Table 1 of the HomomorphicEncryption.org security standard (uniform ternary secrets,
+/// error standard deviation 3.2).
+const STANDARD_MAX_MODULUS_BITS: [[u32; 6]; 3] = [
+    [27, 54, 109, 218, 438, 881],
+    [19, 37, 75, 152, 305, 611],
+    [14, 29, 58, 118, 237, 476],
+];
+
+/// The standard deviation for generating the error, used by the standard parameter sets.
+const STANDARD_STD_DEV: f64 = 3.2;
+
+/// Whether the tables of the standard apply to these distributions: a uniform ternary secret, and
+/// an error at least as wide as the standard's.
+pub(crate) fn follows_standard(secret: SecretDistribution, error: ErrorDistribution) -> bool {
+    secret == SecretDistribution::Ternary && error.std_dev() >= STANDARD_STD_DEV
+}
+
+/// A security level from the HomomorphicEncryption.org security standard,
+/// i.e. the claimed cost (in bits) of the best known attacks on the underlying Ring-LWE problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SecurityLevel {
+    /// 128-bit security
+    Bits128,
+    /// 192-bit security
+    Bits192,
+    /// 256-bit security
+    Bits256,
+}
+
+impl SecurityLevel {
+    /// The number of bits of security.
+    pub fn bits(&self) -> u32 {
+        match self {
+            SecurityLevel::Bits128 => 128,
+            SecurityLevel::Bits192 => 192,
+            SecurityLevel::Bits256 => 256,
+        }
+    }
+
+    /// The largest ciphertext modulus, in bits, that provides this security level for the given
+    /// degree, or `None` if the degree is not covered by the standard (1024 to 32768).
+    ///
+    /// ```rust
+    /// use bfv12::SecurityLevel;
+    /// assert_eq!(SecurityLevel::Bits128.max_modulus_bits(4096), Some(109));
+    /// assert_eq!(SecurityLevel::Bits256.max_modulus_bits(512), None);
+    /// ```
+    pub fn max_modulus_bits(&self, degree: usize) -> Option<u32> {
+        let row = match self {
+            SecurityLevel::Bits128 => 0,
+            SecurityLevel::Bits192 => 1,
+            SecurityLevel::Bits256 => 2,
+        };
+        STANDARD_DEGREES
+            .iter()
+            .position(|d| *d == degree)
+            .map(|i| STANDARD_MAX_MODULUS_BITS[row][i])
+    }
+
+    /// The highest security level for a degree and a ciphertext modulus of `modulus_bits` bits,
+    /// or `None` if the parameters don't reach 128-bit security under the standard.
+    pub(crate) fn of(degree: usize, modulus_bits: u64) -> Option<SecurityLevel> {
+        [
+            SecurityLevel::Bits256,
+            SecurityLevel::Bits192,
+            SecurityLevel::Bits128,
+        ]
+        .iter()
+        .find(|level| {
+            level
+                .max_modulus_bits(degree)
+                .is_some_and(|max_bits| modulus_bits <= max_bits as u64)
+        })
+        .copied()
+    }
+}
+
+/// The parameters for an instantiation of B/FV
+///
+/// * `degree` = N, the degree of the polynomial modulus X^N + 1
+/// * `q` = the ciphertext modulus
+/// * `t` = the plaintext modulus
+/// * `error_distribution` = the distribution of the error
+/// * `rlk_base` = the base T for decomposition during Relinearization Version 1
+/// * `secret_distribution` = the distribution of the secret key coefficients, and of the
+///   randomness u used in encryption, which is uniform ternary for sparse secrets
+/// * `security_policy` = what to do when generating keys for insecure parameters
+///
+/// The parameters are validated once when they are created, and the values derived from them
+/// (`delta = floor(q / t)`, and the NTT tables if q is NTT-friendly) are precomputed.
+/// They are then used for key generation, encryption and evaluation.
+#[derive(Clone, Debug)]
+pub struct Parameters {
+    degree: usize,
+    q: i64,
+    t: i64,
+    error_distribution: ErrorDistribution,
+    rlk_base: i64,
+    secret_distribution: SecretDistribution,
+    security_policy: SecurityPolicy,
+    delta: i64,
+    ntt: Option<Arc<NttTables>>,
+}
+
+/// The ways in which a set of parameters can be invalid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParametersError {
+    /// The degree N must be a power of two.
+    DegreeNotPowerOfTwo(usize),
+    /// The plaintext modulus t must be greater than 1.
+    PlaintextModulusTooSmall(i64),
+    /// The plaintext modulus t must be smaller than the ciphertext modulus q.
+    PlaintextModulusTooLarge { t: i64, q: i64 },
+    /// The ciphertext modulus q must be smaller than 2^62.
+    CiphertextModulusTooLarge(i64),
+    /// The standard deviation must be positive.
+    InvalidStandardDeviation(f64),
+    /// The parameter eta of the centered binomial distribution must be in [1, 32].
+    InvalidBinomialParameter(u32),
+    /// The Hamming weight of a sparse secret must be in [1, N].
+    InvalidHammingWeight {
+        hamming_weight: usize,
+        degree: usize,
+    },
+    /// The relinearization base T must be in [2, q).
+    InvalidRelinearizationBase(i64),
+    /// The ciphertext modulus q must be a prime with q = 1 mod 2N, when the NTT is required.
+    NotNttFriendly { q: i64, degree: usize },
+    /// The plaintext modulus t must be a prime with t = 1 mod 2N, for batching.
+    NotBatchingFriendly { t: i64, degree: usize },
+    /// The base of the integer encoding must be in [2, t).
+    InvalidEncodingBase { base: i64, t: i64 },
+    /// The integer and fraction coefficient counts of the fractional encoding must be at most N/2.
+    InvalidCoefficientCounts {
+        integer_coeff_count: usize,
+        fraction_coeff_count: usize,
+        degree: usize,
+    },
+    /// The degree is not covered by the security standard.
+    NonStandardDegree(usize),
+    /// The estimated security is below the minimum required by the security policy.
+    Insecure { bits: f64, min_bits: u32 },
+}
+
+impl fmt::Display for ParametersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParametersError::DegreeNotPowerOfTwo(degree) => {
+                write!(f, "degree {} is not a power of two", degree)
+            }
+            ParametersError::PlaintextModulusTooSmall(t) => {
+                write!(f, "plaintext modulus {} must be greater than 1", t)
+            }
+            ParametersError::PlaintextModulusTooLarge { t, q } => write!(
+                f,
+                "plaintext modulus {} must be smaller than ciphertext modulus {}",
+                t, q
+            ),
+            ParametersError::CiphertextModulusTooLarge(q) => write!(
+                f,
+                "ciphertext modulus {} must be smaller than 2^{}",
+                q, MAX_Q_BITS
+            ),
+            ParametersError::InvalidStandardDeviation(std_dev) => {
+                write!(f, "standard deviation {} must be positive", std_dev)
+            }
+            ParametersError::InvalidBinomialParameter(eta) => {
+                write!(f, "centered binomial parameter {} must be in [1, 32]", eta)
+            }
+            ParametersError::InvalidHammingWeight {
+                hamming_weight,
+                degree,
+            } => write!(
+                f,
+                "Hamming weight {} must be in [1, {}]",
+                hamming_weight, degree
+            ),
+            ParametersError::InvalidRelinearizationBase(base) => {
+                write!(f, "relinearization base {} must be in [2, q)", base)
+            }
+            ParametersError::NotNttFriendly { q, degree } => write!(
+                f,
+                "ciphertext modulus {} is not a prime equal to 1 mod {}",
+                q,
+                2 * degree
+            ),
+            ParametersError::NotBatchingFriendly { t, degree } => write!(
+                f,
+                "plaintext modulus {} is not a prime equal to 1 mod {}, as required for batching",
+                t,
+                2 * degree
+            ),
+            ParametersError::InvalidEncodingBase { base, t } => {
+                write!(f, "encoding base {} must be in [2, {})", base, t)
+            }
+            ParametersError::InvalidCoefficientCounts {
+                integer_coeff_count,
+                fraction_coeff_count,
+                degree,
+            } => write!(
+                f,
+                "integer and fraction coefficient counts {} and {} must be at most N/2 = {}",
+                integer_coeff_count,
+                fraction_coeff_count,
+                degree / 2
+            ),
+            ParametersError::NonStandardDegree(degree) => write!(
+                f,
+                "degree {} is not covered by the security standard (1024 to 32768)",
+                degree
+            ),
+            ParametersError::Insecure { bits, min_bits } => write!(
+                f,
+                "parameters provide an estimated {:.1} bits of security, below the minimum of {}",
+                bits, min_bits
+            ),
+        }
+    }
+}
+
+impl Error for ParametersError {}
+
+impl PartialEq for Parameters {
+    fn eq(&self, other: &Parameters) -> bool {
+        // The remaining fields are derived from these.
+        self.degree == other.degree
+            && self.q == other.q
+            && self.t == other.t
+            && self.error_distribution == other.error_distribution
+            && self.rlk_base == other.rlk_base
+            && self.secret_distribution == other.secret_distribution
+    }
+}
+
+impl Parameters {
+    /// Create and validate a set of parameters.
+    /// The error is sampled from the discrete Gaussian with standard deviation `std_dev`, and the
+    /// secret key from {0, 1}, see `with_error_distribution` and `with_secret_distribution`.
+    /// The relinearization base defaults to `log_2(q)`, see `with_rlk_base` to change it.
+    ///
+    /// * `degree`: the degree N of the polynomials used for encoding and encrypting messages,
+    ///   which must be a power of two
+    /// * `q`: the ciphertext modulus, in (t, 2^62)
+    /// * `t`: the plaintext modulus, greater than 1
+    /// * `std_dev`: the standard deviation for generating the error
+    ///
+    /// ```rust
+    /// use bfv12::Parameters;
+    ///
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+    /// assert_eq!(params.delta(), 5461);
+    ///
+    /// // The degree must be a power of two
+    /// assert!(Parameters::new(6, 65536, 12, 3.2).is_err());
+    /// ```
+    pub fn new(degree: usize, q: i64, t: i64, std_dev: f64) -> Result<Parameters, ParametersError> {
+        if !degree.is_power_of_two() {
+            return Err(ParametersError::DegreeNotPowerOfTwo(degree));
+        }
+        if t <= 1 {
+            return Err(ParametersError::PlaintextModulusTooSmall(t));
+        }
+        if t >= q {
+            return Err(ParametersError::PlaintextModulusTooLarge { t, q });
+        }
+        if q >= 1 << MAX_Q_BITS {
+            return Err(ParametersError::CiphertextModulusTooLarge(q));
+        }
+
+        Parameters {
+            degree,
+            q,
+            t,
+            error_distribution: ErrorDistribution::Gaussian { std_dev: 1.0 },
+            rlk_base: (q as f64).log2() as i64,
+            secret_distribution: SecretDistribution::Binary,
+            security_policy: SecurityPolicy::Ignore,
+            delta: q / t,
+            ntt: NttTables::get(q, degree),
+        }
+        .with_error_distribution(ErrorDistribution::Gaussian { std_dev })
+    }
+
+    /// Create a standard set of parameters, from the HomomorphicEncryption.org security standard.
+    ///
+    /// The ciphertext modulus q is the largest NTT-friendly prime allowed for the degree and
+    /// security level. Since q must fit in an i64, it is capped at 61 bits, which only makes the
+    /// parameters more secure; see `RnsBasis::standard` for the full-size modulus.
+    /// The error is sampled from the discrete Gaussian with standard deviation 3.2, and the
+    /// secret key from {-1, 0, 1}, as assumed by the standard.
+    ///
+    /// * `degree`: the degree N, one of 1024, 2048, 4096, 8192, 16384 or 32768
+    /// * `t`: the plaintext modulus
+    /// * `level`: the security level
+    ///
+    /// ```rust
+    /// use bfv12::{Parameters, SecurityLevel};
+    ///
+    /// let params = Parameters::standard(2048, 256, SecurityLevel::Bits128).unwrap();
+    /// assert_eq!(params.security_level(), Some(SecurityLevel::Bits128));
+    /// assert!(params.is_ntt_friendly());
+    /// ```
+    pub fn standard(
+        degree: usize,
+        t: i64,
+        level: SecurityLevel,
+    ) -> Result<Parameters, ParametersError> {
+        let max_bits = level
+            .max_modulus_bits(degree)
+            .ok_or(ParametersError::NonStandardDegree(degree))?;
+        let q = ntt::find_ntt_prime(max_bits.min(MAX_Q_BITS - 1), degree)
+            .expect("the standard degrees have NTT-friendly primes");
+        Parameters::new(degree, q, t, STANDARD_STD_DEV)?
+            .with_secret_distribution(SecretDistribution::Ternary)
+    }
+
+    /// Set the base T for decomposition during Relinearization Version 1.
+    ///
+    /// The base can be chosen to trade off relinearisation time and space, for error accumulation.
+    /// Choosing T = ceil(sqrt(q)) will minimize relinearisation time and space, at the expense of error.
+    /// Choosing T = log_2(q) will decrease error at the cost of relinearisation time and space.
+    ///
+    /// ```rust
+    /// use bfv12::Parameters;
+    ///
+    /// let q = 65536;
+    /// let rlk_base = (q as f64).sqrt().ceil() as i64;
+    /// let params = Parameters::new(4, q, 12, 3.2).unwrap().with_rlk_base(rlk_base).unwrap();
+    /// assert_eq!(params.rlk_base(), 256);
+    /// ```
+    pub fn with_rlk_base(mut self, rlk_base: i64) -> Result<Parameters, ParametersError> {
+        if rlk_base < 2 || rlk_base >= self.q {
+            return Err(ParametersError::InvalidRelinearizationBase(rlk_base));
+        }
+        self.rlk_base = rlk_base;
+        Ok(self)
+    }
+
+    /// Set the distribution of the error.
+    ///
+    /// ```rust
+    /// use bfv12::{ErrorDistribution, Parameters};
+    ///
+    /// let params = Parameters::new(1024, 12289, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_error_distribution(ErrorDistribution::CenteredBinomial { eta: 8 })
+    ///     .unwrap();
+    /// assert_eq!(params.std_dev(), 2.0);
+    /// ```
+    pub fn with_error_distribution(
+        mut self,
+        error_distribution: ErrorDistribution,
+    ) -> Result<Parameters, ParametersError> {
+        check_error_distribution(error_distribution)?;
+        self.error_distribution = error_distribution;
+        Ok(self)
+    }
+
+    /// Set the distribution of the secret key coefficients, which is also used for the
+    /// randomness u in encryption, except that u is uniform ternary for sparse secrets.
+    ///
+    /// ```rust
+    /// use bfv12::{Parameters, SecretDistribution};
+    ///
+    /// let params = Parameters::new(1024, 12289, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_secret_distribution(SecretDistribution::SparseTernary { hamming_weight: 64 })
+    ///     .unwrap();
+    /// ```
+    pub fn with_secret_distribution(
+        mut self,
+        secret_distribution: SecretDistribution,
+    ) -> Result<Parameters, ParametersError> {
+        check_secret_distribution(secret_distribution, self.degree)?;
+        self.secret_distribution = secret_distribution;
+        Ok(self)
+    }
+
+    /// Require that q is NTT-friendly (a prime with q = 1 mod 2N), so that multiplication
+    /// in R_q always uses the NTT rather than falling back to schoolbook multiplication.
+    ///
+    /// ```rust
+    /// use bfv12::Parameters;
+    ///
+    /// assert!(Parameters::new(1024, 12289, 12, 3.2).unwrap().require_ntt().is_ok());
+    /// assert!(Parameters::new(1024, 65536, 12, 3.2).unwrap().require_ntt().is_err());
+    /// ```
+    pub fn require_ntt(self) -> Result<Parameters, ParametersError> {
+        match self.ntt {
+            Some(_) => Ok(self),
+            None => Err(ParametersError::NotNttFriendly {
+                q: self.q,
+                degree: self.degree,
+            }),
+        }
+    }
+
+    /// Set what to do when keys are generated for these parameters, if they are estimated to be
+    /// insecure by `estimate_security`. By default, the security is not checked.
+    ///
+    /// ```rust,should_panic
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey, SecurityPolicy};
+    ///
+    /// let params = Parameters::new(4, 65536, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_security_policy(SecurityPolicy::Reject { min_bits: 128 });
+    ///
+    /// // Panics, since these toy parameters provide no security
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// ```
+    pub fn with_security_policy(mut self, security_policy: SecurityPolicy) -> Parameters {
+        self.security_policy = security_policy;
+        self
+    }
+
+    /// The degree N of the polynomials used for encoding and encrypting messages
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// The ciphertext modulus
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+
+    /// The plaintext modulus
+    pub fn t(&self) -> i64 {
+        self.t
+    }
+
+    /// The distribution of the error
+    pub fn error_distribution(&self) -> ErrorDistribution {
+        self.error_distribution
+    }
+
+    /// The standard deviation of the error
+    pub fn std_dev(&self) -> f64 {
+        self.error_distribution.std_dev()
+    }
+
+    /// The base T for decomposition during Relinearization Version 1
+    pub fn rlk_base(&self) -> i64 {
+        self.rlk_base
+    }
+
+    // The number of levels to decompose into during Relinearization Version 1: l = floor(log_T(q))
+    pub(crate) fn rlk_levels(&self) -> usize {
+        (self.q as f64).log(self.rlk_base as f64).floor() as usize
+    }
+
+    /// The distribution of the secret key coefficients, and of the randomness u used in
+    /// encryption, which is uniform ternary for sparse secrets
+    pub fn secret_distribution(&self) -> SecretDistribution {
+        self.secret_distribution
+    }
+
+    /// What to do when keys are generated for insecure parameters
+    pub fn security_policy(&self) -> SecurityPolicy {
+        self.security_policy
+    }
+
+    /// The scaling factor for encoding plaintexts in ciphertexts, `delta = floor(q / t)`
+    pub fn delta(&self) -> i64 {
+        self.delta
+    }
+
+    /// An identifier of the parameters: a hash of everything but the security policy, which is
+    /// stable across versions of the crate. Armored keys hold it in their header.
+    pub fn id(&self) -> u64 {
+        serialization::params_hash(self)
+    }
+
+    /// Whether q is NTT-friendly, so that multiplication in R_q uses the NTT.
+    pub fn is_ntt_friendly(&self) -> bool {
+        self.ntt.is_some()
+    }
+
+    /// The security level claimed for these parameters by the HomomorphicEncryption.org standard,
+    /// or `None` if they don't reach 128-bit security (or the degree isn't covered by the standard).
+    ///
+    /// The tables of the standard assume a uniform ternary secret and an error with standard
+    /// deviation 3.2, so other secret distributions, or narrower errors, are `None` as well;
+    /// use `estimate_security` for those.
+    ///
+    /// ```rust
+    /// use bfv12::{ErrorDistribution, Parameters, SecretDistribution, SecurityLevel};
+    ///
+    /// // Toy parameters provide no security
+    /// let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+    /// assert_eq!(params.security_level(), None);
+    ///
+    /// let params = Parameters::new(1024, 12289, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_secret_distribution(SecretDistribution::Ternary)
+    ///     .unwrap();
+    /// assert_eq!(params.security_level(), Some(SecurityLevel::Bits256));
+    ///
+    /// // The standard doesn't cover binary secrets, or errors this narrow
+    /// let binary = params.clone().with_secret_distribution(SecretDistribution::Binary).unwrap();
+    /// assert_eq!(binary.security_level(), None);
+    /// let narrow = params
+    ///     .with_error_distribution(ErrorDistribution::CenteredBinomial { eta: 1 })
+    ///     .unwrap();
+    /// assert_eq!(narrow.security_level(), None);
+    /// ```
+    pub fn security_level(&self) -> Option<SecurityLevel> {
+        if !follows_standard(self.secret_distribution, self.error_distribution) {
+            return None;
+        }
+        let modulus_bits = 64 - self.q.leading_zeros() as u64;
+        SecurityLevel::of(self.degree, modulus_bits)
+    }
+
+    /// Estimate the security of these parameters against the primal uSVP attack,
+    /// see `estimate_security`.
+    ///
+    /// ```rust
+    /// use bfv12::Parameters;
+    ///
+    /// let params = Parameters::new(2048, 1 << 40, 12, 3.2).unwrap();
+    /// assert!(params.estimate_security().bits > 128.0);
+    /// ```
+    pub fn estimate_security(&self) -> SecurityEstimate {
+        security::estimate_security(
+            self.degree,
+            self.modulus_bits(),
+            self.std_dev(),
+            self.secret_distribution,
+        )
+    }
+
+    /// Check the estimated security of these parameters against the minimum required by
+    /// the security policy. Parameters with the `Ignore` policy always pass.
+    pub fn check_security(&self) -> Result<(), ParametersError> {
+        check_security(self.security_policy, || self.estimate_security())
+    }
+
+    /// Apply the security policy of these parameters, as is done when generating keys.
+    /// Returns an error if the parameters are rejected, and the warning if they are estimated to
+    /// be insecure under the `Warn` policy. Key generation discards the warning, so call this
+    /// beforehand to report it.
+    ///
+    /// ```rust
+    /// use bfv12::{Parameters, ParametersError, SecurityPolicy};
+    ///
+    /// let params = Parameters::new(4, 65536, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_security_policy(SecurityPolicy::Warn { min_bits: 80 });
+    /// let warning = params.try_enforce_security_policy().unwrap();
+    /// assert!(matches!(warning, Some(ParametersError::Insecure { .. })));
+    /// ```
+    pub fn try_enforce_security_policy(&self) -> Result<Option<ParametersError>, ParametersError> {
+        enforce_security_policy(self.security_policy, self.check_security())
+    }
+
+    // Multiply two polynomials in R_q, with the NTT tables of the parameters if q is NTT-friendly.
+    pub(crate) fn mul_mod(&self, a: &Poly, b: &Poly) -> Poly {
+        a.mul_mod(b, self.q, self.degree, self.ntt.as_deref())
+    }
+
+    // log_2(q)
+    fn modulus_bits(&self) -> f64 {
+        (self.q as f64).log2()
+    }
+}
+
+/// The parameters for an instantiation of B/FV with the ciphertext modulus q represented by an
+/// RNS basis, so that q can be hundreds of bits
+///
+/// * `basis` = the RNS basis of q, which also fixes the degree N
+/// * `t` = the plaintext modulus
+/// * `error_distribution` = the distribution of the error
+/// * `secret_distribution` = the distribution of the secret key coefficients, and of the
+///   randomness u used in encryption, which is uniform ternary for sparse secrets
+/// * `security_policy` = what to do when generating keys for insecure parameters
+///
+/// As with `Parameters`, they are validated once when they are created, and
+/// `delta = floor(q / t)` is precomputed. Relinearization uses the RNS decomposition of q, so
+/// there is no relinearization base.
+#[derive(Clone, Debug)]
+pub struct RnsParameters {
+    basis: Arc<RnsBasis>,
+    t: i64,
+    error_distribution: ErrorDistribution,
+    secret_distribution: SecretDistribution,
+    security_policy: SecurityPolicy,
+    delta: BigInt,
+}
+
+impl PartialEq for RnsParameters {
+    fn eq(&self, other: &RnsParameters) -> bool {
+        // The basis is determined by its primes and degree, and delta by q and t.
+        self.basis.moduli() == other.basis.moduli()
+            && self.basis.degree() == other.basis.degree()
+            && self.t == other.t
+            && self.error_distribution == other.error_distribution
+            && self.secret_distribution == other.secret_distribution
+    }
+}
+
+impl RnsParameters {
+    /// Create and validate a set of parameters for a ciphertext modulus represented by an RNS
+    /// basis. The error is sampled from the discrete Gaussian with standard deviation `std_dev`,
+    /// and the secret key from {0, 1}, see `with_error_distribution` and `with_secret_distribution`.
+    ///
+    /// * `basis`: the RNS basis of the ciphertext modulus q
+    /// * `t`: the plaintext modulus, in (1, q)
+    /// * `std_dev`: the standard deviation for generating the error
+    ///
+    /// ```rust
+    /// use bfv12::{RnsBasis, RnsParameters};
+    ///
+    /// // A 200-bit ciphertext modulus, represented by four 50-bit primes
+    /// let basis = RnsBasis::generate(4, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    /// assert_eq!(params.degree(), 1024);
+    /// ```
+    pub fn new(
+        basis: &Arc<RnsBasis>,
+        t: i64,
+        std_dev: f64,
+    ) -> Result<RnsParameters, ParametersError> {
+        if t <= 1 {
+            return Err(ParametersError::PlaintextModulusTooSmall(t));
+        }
+        let q = basis.modulus();
+        if BigInt::from(t) >= *q {
+            // q is smaller than t, so it fits in an i64
+            let q = q.to_i64().unwrap();
+            return Err(ParametersError::PlaintextModulusTooLarge { t, q });
+        }
+
+        RnsParameters {
+            basis: basis.clone(),
+            t,
+            error_distribution: ErrorDistribution::Gaussian { std_dev: 1.0 },
+            secret_distribution: SecretDistribution::Binary,
+            security_policy: SecurityPolicy::Ignore,
+            delta: q / t,
+        }
+        .with_error_distribution(ErrorDistribution::Gaussian { std_dev })
+    }
+
+    /// Set the distribution of the error, as with `Parameters::with_error_distribution`.
+    ///
+    /// ```rust
+    /// use bfv12::{ErrorDistribution, RnsBasis, RnsParameters};
+    ///
+    /// let basis = RnsBasis::generate(4, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_error_distribution(ErrorDistribution::CenteredBinomial { eta: 8 })
+    ///     .unwrap();
+    /// assert_eq!(params.std_dev(), 2.0);
+    /// ```
+    pub fn with_error_distribution(
+        mut self,
+        error_distribution: ErrorDistribution,
+    ) -> Result<RnsParameters, ParametersError> {
+        check_error_distribution(error_distribution)?;
+        self.error_distribution = error_distribution;
+        Ok(self)
+    }
+
+    /// Set the distribution of the secret key coefficients, which is also used for the
+    /// randomness u in encryption, as with `Parameters::with_secret_distribution`.
+    ///
+    /// ```rust
+    /// use bfv12::{RnsBasis, RnsParameters, SecretDistribution, SecurityLevel};
+    ///
+    /// let basis = RnsBasis::standard(4096, SecurityLevel::Bits128).unwrap();
+    /// let params = RnsParameters::new(&basis, 12, 3.2)
+    ///     .unwrap()
+    ///     .with_secret_distribution(SecretDistribution::Ternary)
+    ///     .unwrap();
+    /// assert_eq!(params.security_level(), Some(SecurityLevel::Bits128));
+    /// ```
+    pub fn with_secret_distribution(
+        mut self,
+        secret_distribution: SecretDistribution,
+    ) -> Result<RnsParameters, ParametersError> {
+        check_secret_distribution(secret_distribution, self.degree())?;
+        self.secret_distribution = secret_distribution;
+        Ok(self)
+    }
+
+    /// Set what to do when keys are generated for these parameters, if they are estimated to be
+    /// insecure by `estimate_security`, as with `Parameters::with_security_policy`.
+    /// By default, the security is not checked.
+    pub fn with_security_policy(mut self, security_policy: SecurityPolicy) -> RnsParameters {
+        self.security_policy = security_policy;
+        self
+    }
+
+    /// The RNS basis of the ciphertext modulus
+    pub fn basis(&self) -> &Arc<RnsBasis> {
+        &self.basis
+    }
+
+    /// The degree N of the polynomials used for encoding and encrypting messages
+    pub fn degree(&self) -> usize {
+        self.basis.degree()
+    }
+
+    /// The plaintext modulus
+    pub fn t(&self) -> i64 {
+        self.t
+    }
+
+    /// The distribution of the error
+    pub fn error_distribution(&self) -> ErrorDistribution {
+        self.error_distribution
+    }
+
+    /// The standard deviation of the error
+    pub fn std_dev(&self) -> f64 {
+        self.error_distribution.std_dev()
+    }
+
+    /// The distribution of the secret key coefficients, and of the randomness u used in
+    /// encryption, which is uniform ternary for sparse secrets
+    pub fn secret_distribution(&self) -> SecretDistribution {
+        self.secret_distribution
+    }
+
+    /// What to do when keys are generated for insecure parameters
+    pub fn security_policy(&self) -> SecurityPolicy {
+        self.security_policy
+    }
+
+    /// An identifier of the parameters: a hash of everything but the security policy,
+    /// as with `Parameters::id`.
+    pub fn id(&self) -> u64 {
+        serialization::rns_params_hash(self)
+    }
+
+    // The scaling factor for encoding plaintexts in ciphertexts, `delta = floor(q / t)`
+    pub(crate) fn delta(&self) -> &BigInt {
+        &self.delta
+    }
+
+    /// The security level claimed for these parameters by the HomomorphicEncryption.org standard,
+    /// or `None`, as with `Parameters::security_level`.
+    ///
+    /// ```rust
+    /// use bfv12::{RnsBasis, RnsParameters};
+    ///
+    /// // The standard doesn't cover binary secrets
+    /// let basis = RnsBasis::generate(2, 50, 4096);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    /// assert_eq!(params.security_level(), None);
+    /// ```
+    pub fn security_level(&self) -> Option<SecurityLevel> {
+        if !follows_standard(self.secret_distribution, self.error_distribution) {
+            return None;
+        }
+        self.basis.security_level()
+    }
+
+    /// Estimate the security of these parameters against the primal uSVP attack,
+    /// see `estimate_security`.
+    ///
+    /// ```rust
+    /// use bfv12::{RnsBasis, RnsParameters};
+    ///
+    /// let basis = RnsBasis::generate(2, 50, 4096);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    /// assert!(params.estimate_security().bits > 128.0);
+    /// ```
+    pub fn estimate_security(&self) -> SecurityEstimate {
+        security::estimate_security(
+            self.degree(),
+            self.basis.modulus().to_f64().unwrap().log2(),
+            self.std_dev(),
+            self.secret_distribution,
+        )
+    }
+
+    /// Check the estimated security of these parameters against the minimum required by
+    /// the security policy. Parameters with the `Ignore` policy always pass.
+    pub fn check_security(&self) -> Result<(), ParametersError> {
+        check_security(self.security_policy, || self.estimate_security())
+    }
+
+    /// Apply the security policy of these parameters, as is done when generating keys, see
+    /// `Parameters::try_enforce_security_policy`.
+    pub fn try_enforce_security_policy(&self) -> Result<Option<ParametersError>, ParametersError> {
+        enforce_security_policy(self.security_policy, self.check_security())
+    }
+}
+
+// Check that the parameters of an error distribution are valid.
+fn check_error_distribution(error_distribution: ErrorDistribution) -> Result<(), ParametersError> {
+    match error_distribution {
+        ErrorDistribution::Gaussian { std_dev } if std_dev <= 0.0 || !std_dev.is_finite() => {
+            Err(ParametersError::InvalidStandardDeviation(std_dev))
+        }
+        ErrorDistribution::CenteredBinomial { eta } if !(1..=32).contains(&eta) => {
+            Err(ParametersError::InvalidBinomialParameter(eta))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Check that the Hamming weight of a sparse secret is in [1, N].
+fn check_secret_distribution(
+    secret_distribution: SecretDistribution,
+    degree: usize,
+) -> Result<(), ParametersError> {
+    match secret_distribution {
+        SecretDistribution::SparseTernary { hamming_weight }
+            if hamming_weight == 0 || hamming_weight > degree =>
+        {
+            Err(ParametersError::InvalidHammingWeight {
+                hamming_weight,
+                degree,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+// Check an estimate of the security against the minimum required by a security policy.
+fn check_security<F: FnOnce() -> SecurityEstimate>(
+    security_policy: SecurityPolicy,
+    estimate: F,
+) -> Result<(), ParametersError> {
+    let min_bits = match security_policy {
+        SecurityPolicy::Ignore => return Ok(()),
+        SecurityPolicy::Warn { min_bits } | SecurityPolicy::Reject { min_bits } => min_bits,
+    };
+    let bits = estimate().bits;
+    if bits < min_bits as f64 {
+        return Err(ParametersError::Insecure { bits, min_bits });
+    }
+    Ok(())
+}
+
+// Keep the error of `check_security` as a warning under the `Warn` policy.
+fn enforce_security_policy(
+    security_policy: SecurityPolicy,
+    checked: Result<(), ParametersError>,
+) -> Result<Option<ParametersError>, ParametersError> {
+    match (checked, security_policy) {
+        (Err(err), SecurityPolicy::Warn { .. }) => Ok(Some(err)),
+        (result, _) => result.map(|()| None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation_test() {
+        assert!(Parameters::new(4, 65536, 12, 3.2).is_ok());
+        assert_eq!(
+            Parameters::new(12, 65536, 12, 3.2),
+            Err(ParametersError::DegreeNotPowerOfTwo(12))
+        );
+        assert_eq!(
+            Parameters::new(4, 65536, 1, 3.2),
+            Err(ParametersError::PlaintextModulusTooSmall(1))
+        );
+        assert_eq!(
+            Parameters::new(4, 65536, 65536, 3.2),
+            Err(ParametersError::PlaintextModulusTooLarge { t: 65536, q: 65536 })
+        );
+        assert_eq!(
+            Parameters::new(4, 1 << 62, 12, 3.2),
+            Err(ParametersError::CiphertextModulusTooLarge(1 << 62))
+        );
+        assert_eq!(
+            Parameters::new(4, 65536, 12, -1.0),
+            Err(ParametersError::InvalidStandardDeviation(-1.0))
+        );
+        assert_eq!(
+            Parameters::new(4, 65536, 12, 3.2)
+                .unwrap()
+                .with_error_distribution(ErrorDistribution::CenteredBinomial { eta: 33 }),
+            Err(ParametersError::InvalidBinomialParameter(33))
+        );
+        assert_eq!(
+            Parameters::new(4, 65536, 12, 3.2)
+                .unwrap()
+                .with_secret_distribution(SecretDistribution::SparseTernary { hamming_weight: 5 }),
+            Err(ParametersError::InvalidHammingWeight {
+                hamming_weight: 5,
+                degree: 4
+            })
+        );
+        assert_eq!(
+            Parameters::new(4, 65536, 12, 3.2)
+                .unwrap()
+                .with_rlk_base(65536),
+            Err(ParametersError::InvalidRelinearizationBase(65536))
+        );
+    }
+
+    #[test]
+    fn precomputation_test() {
+        let params = Parameters::new(8, 65536, 12, 3.2).unwrap();
+        assert_eq!(params.delta(), 5461);
+        assert_eq!(params.rlk_base(), 16);
+        assert!(!params.is_ntt_friendly());
+
+        let q = ntt::find_ntt_prime(40, 1024).unwrap();
+        let params = Parameters::new(1024, q, 12, 3.2).unwrap();
+        assert!(params.is_ntt_friendly());
+        assert_eq!(params.require_ntt(), Parameters::new(1024, q, 12, 3.2));
+    }
+
+    #[test]
+    fn security_policy_test() {
+        let toy = Parameters::new(4, 65536, 12, 3.2).unwrap();
+        assert_eq!(toy.check_security(), Ok(()));
+        let toy = toy.with_security_policy(SecurityPolicy::Warn { min_bits: 80 });
+        assert!(matches!(
+            toy.check_security(),
+            Err(ParametersError::Insecure { min_bits: 80, .. })
+        ));
+        assert!(matches!(
+            toy.try_enforce_security_policy(),
+            Ok(Some(ParametersError::Insecure { min_bits: 80, .. }))
+        ));
+        let toy = toy.with_security_policy(SecurityPolicy::Reject { min_bits: 80 });
+        assert!(toy.try_enforce_security_policy().is_err());
+
+        let params = Parameters::standard(2048, 12, SecurityLevel::Bits128)
+            .unwrap()
+            .with_security_policy(SecurityPolicy::Reject { min_bits: 120 });
+        assert_eq!(params.check_security(), Ok(()));
+        assert_eq!(params.try_enforce_security_policy(), Ok(None));
+    }
+
+    #[test]
+    fn rns_parameters_test() {
+        let basis = RnsBasis::new(vec![12289, 40961], 1024);
+        let q = 12289 * 40961;
+        assert_eq!(
+            RnsParameters::new(&basis, 1, 3.2),
+            Err(ParametersError::PlaintextModulusTooSmall(1))
+        );
+        assert_eq!(
+            RnsParameters::new(&basis, 1 << 40, 3.2),
+            Err(ParametersError::PlaintextModulusTooLarge { t: 1 << 40, q })
+        );
+        assert_eq!(
+            RnsParameters::new(&basis, 12, 0.0),
+            Err(ParametersError::InvalidStandardDeviation(0.0))
+        );
+
+        let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+        assert_eq!(
+            params
+                .clone()
+                .with_error_distribution(ErrorDistribution::CenteredBinomial { eta: 33 }),
+            Err(ParametersError::InvalidBinomialParameter(33))
+        );
+        assert_eq!(
+            params
+                .clone()
+                .with_secret_distribution(SecretDistribution::SparseTernary {
+                    hamming_weight: 2048
+                }),
+            Err(ParametersError::InvalidHammingWeight {
+                hamming_weight: 2048,
+                degree: 1024
+            })
+        );
+        assert_eq!(params.delta(), &BigInt::from(q / 12));
+        assert_eq!(params.try_enforce_security_policy(), Ok(None));
+        let params = params.with_security_policy(SecurityPolicy::Reject { min_bits: 300 });
+        assert!(matches!(
+            params.try_enforce_security_policy(),
+            Err(ParametersError::Insecure { min_bits: 300, .. })
+        ));
+    }
+
+    #[test]
+    fn standard_test() {
+        let levels = [
+            SecurityLevel::Bits128,
+            SecurityLevel::Bits192,
+            SecurityLevel::Bits256,
+        ];
+        for degree in STANDARD_DEGREES.iter() {
+            for level in levels.iter() {
+                let params = Parameters::standard(*degree, 2, *level).unwrap();
+                assert!(params.is_ntt_friendly());
+                assert!(params.security_level().unwrap() >= *level);
+                let q_bits = 64 - params.q().leading_zeros();
+                assert!(q_bits <= level.max_modulus_bits(*degree).unwrap());
+            }
+        }
+
+        // 2048 at 128 bits allows a 54-bit modulus
+        let params = Parameters::standard(2048, 2, SecurityLevel::Bits128).unwrap();
+        assert_eq!(64 - params.q().leading_zeros(), 54);
+        assert_eq!(params.security_level(), Some(SecurityLevel::Bits128));
+
+        assert_eq!(
+            Parameters::standard(512, 2, SecurityLevel::Bits128),
+            Err(ParametersError::NonStandardDegree(512))
+        );
+        // t must still be smaller than q
+        assert!(Parameters::standard(1024, 1 << 20, SecurityLevel::Bits256).is_err());
+    }
+}
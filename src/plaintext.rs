@@ -1,7 +1,11 @@
-use super::ciphertext::Ciphertext;
-use super::keys::PublicKey;
+use super::ciphertext::{Ciphertext, RnsCiphertext};
+use super::error::{self, Error};
+use super::keys::{PublicKey, RnsPublicKey};
+use super::noise;
+use super::params::{Parameters, ParametersError};
 use super::poly::Poly;
 use super::random_source;
+use super::rns::RnsPoly;
 use rand::{CryptoRng, RngCore};
 
 /// A BFV12 Plaintext (an encoded message)
@@ -27,30 +31,48 @@ impl Plaintext {
         Plaintext::new_from_poly(Poly::new(val), t)
     }
 
+    /// Instantiate a new Plaintext as in `new`, returning an error instead of panicking if the
+    /// plaintext modulus is not greater than 1
+    ///
+    /// ```rust
+    /// use bfv12::{Error, ParametersError, Plaintext};
+    /// assert!(Plaintext::try_new(vec![0, 1, 2, 3], 4).is_ok());
+    /// assert_eq!(
+    ///     Plaintext::try_new(vec![0, 0, 0, 0], 1),
+    ///     Err(Error::InvalidParameters(ParametersError::PlaintextModulusTooSmall(1)))
+    /// );
+    /// ```
+    pub fn try_new(val: Vec<i64>, t: i64) -> Result<Plaintext, Error> {
+        if t <= 1 {
+            return Err(ParametersError::PlaintextModulusTooSmall(t).into());
+        }
+        Ok(Plaintext::new(val, t))
+    }
+
     pub(crate) fn new_from_poly(poly: Poly, t: i64) -> Plaintext {
         // The plaintext space is taken as R_t for some integer t > 1.
         assert!(t > 1);
         Plaintext { poly, t }
     }
 
-    /// Instantiate a new random Plaintext uniformly over [0, t) with length `degree`
+    /// Instantiate a new random Plaintext uniformly over [0, t) with length `degree`,
+    /// for the plaintext modulus t and degree of the given parameters
     ///
-    /// * `degree`: the degree (length) of the newly generated plaintext
-    /// * `t`: the plaintext modulus
+    /// * `params`: the parameters for this instantiation of B/FV
     /// # `rng`: the RNG used to generate randomness. Any Rng that imlements RngCore + CryptoRng can be used.
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::Plaintext;
-    /// let rand_pt = Plaintext::rand(10, 4, &mut rng);
+    /// use bfv12::{Parameters, Plaintext};
+    /// let params = Parameters::new(16, 65536, 4, 3.2).unwrap();
+    /// let rand_pt = Plaintext::rand(&params, &mut rng);
     /// ```
-    pub fn rand<T: RngCore + CryptoRng>(degree: usize, t: i64, rng: &mut T) -> Plaintext {
-        assert!(t > 1);
+    pub fn rand<T: RngCore + CryptoRng>(params: &Parameters, rng: &mut T) -> Plaintext {
         Plaintext {
-            poly: random_source::get_uniform(t, degree, rng),
-            t,
+            poly: random_source::get_uniform(params.t(), params.degree(), rng),
+            t: params.t(),
         }
     }
 
@@ -66,53 +88,152 @@ impl Plaintext {
         self.poly.clone()
     }
 
-    /// Encrypt a plaintext with a given public key
+    /// The plaintext modulus t
+    pub(crate) fn t(&self) -> i64 {
+        self.t
+    }
+
+    /// Encrypt a plaintext with a given public key.
+    /// The plaintext must have the plaintext modulus and degree of the public key's parameters.
     ///
     /// * `pub_key`: the public key used to encrypt plaintext
-    /// * `std_dev`: the standard deviation used for generating the error in the encryption
     /// # `rng`: the RNG used to generate randomness. Any Rng that imlements RngCore + CryptoRng can be used.
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
-    /// # let std_dev = 3.2;
-    /// # let degree = 4;
-    /// # let q = 65536;
-    /// # let t = 4;
     /// #
-    /// use bfv12::{Plaintext, SecretKey};
-    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// use bfv12::{Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], params.t());
     ///
-    /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
     ///
-    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// let ct = pt.encrypt(&public_key, &mut rng);
     /// ```
-    pub fn encrypt<T: RngCore + CryptoRng>(
+    pub fn encrypt<T: RngCore + CryptoRng>(&self, pub_key: &PublicKey, rng: &mut T) -> Ciphertext {
+        self.try_encrypt(pub_key, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Encrypt a plaintext with a given public key as in `encrypt`, returning an error instead
+    /// of panicking if the plaintext modulus or degree don't match the public key's parameters.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Error, Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(4, 65536, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2], params.t());
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    ///
+    /// let err = pt.try_encrypt(&public_key, &mut rng).unwrap_err();
+    /// assert_eq!(err, Error::DegreeMismatch { expected: 4, found: 3 });
+    /// ```
+    pub fn try_encrypt<T: RngCore + CryptoRng>(
         &self,
         pub_key: &PublicKey,
-        std_dev: f64,
         rng: &mut T,
-    ) -> Ciphertext {
-        assert_eq!(self.poly.degree(), pub_key.p_0.degree());
-        let q = pub_key.q;
-        let degree = self.poly.degree();
-        let m = self.poly.clone();
-
-        let u = random_source::get_uniform(2, degree, rng);
-        let e_1 = random_source::get_gaussian(std_dev, degree, rng);
-        let e_2 = random_source::get_gaussian(std_dev, degree, rng);
+    ) -> Result<Ciphertext, Error> {
+        let params = &pub_key.params;
+        let (degree, q) = (params.degree(), params.q());
+        self.check(params)?;
+        let m = self.poly.clone() % (self.t, degree);
 
-        let delta = (q as f64 / self.t as f64).floor() as i64;
+        let u = params
+            .secret_distribution()
+            .randomness()
+            .sample(degree, rng);
+        let e_1 = params.error_distribution().sample(degree, rng);
+        let e_2 = params.error_distribution().sample(degree, rng);
 
-        let c_0 = (pub_key.p_0.clone() * u.clone() + e_1 + m * delta) % (q, degree);
-        let c_1 = (pub_key.p_1.clone() * u + e_2) % (q, degree);
+        let c_0 = (params.mul_mod(&pub_key.p_0, &u) + e_1 + m * params.delta()) % (q, degree);
+        let c_1 = (params.mul_mod(pub_key.p_1(), &u) + e_2) % (q, degree);
 
-        Ciphertext {
+        Ok(Ciphertext {
             c_0,
             c_1,
-            q,
-            t: self.t,
+            params: params.clone(),
+            key_id: pub_key.id,
+            noise_bound: noise::fresh_public(params),
+        })
+    }
+
+    // Check that the plaintext modulus and degree match the parameters.
+    pub(crate) fn check(&self, params: &Parameters) -> Result<(), Error> {
+        self.check_with(params.t(), params.degree())
+    }
+
+    pub(crate) fn check_with(&self, t: i64, degree: usize) -> Result<(), Error> {
+        if self.t != t {
+            return Err(Error::PlaintextModulusMismatch {
+                expected: t,
+                found: self.t,
+            });
         }
+        error::check_degree(degree, self.poly.degree())
+    }
+
+    /// Encrypt a plaintext with a public key for a ciphertext modulus represented by an RNS basis
+    ///
+    /// * `pub_key`: the public key used to encrypt plaintext
+    /// # `rng`: the RNG used to generate randomness. Any Rng that imlements RngCore + CryptoRng can be used.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Plaintext, RnsBasis, RnsParameters, SecretKey};
+    /// let basis = RnsBasis::generate(3, 60, 4);
+    /// let params = RnsParameters::new(&basis, 4, 3.2).unwrap();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], params.t());
+    ///
+    /// let secret_key = SecretKey::generate_rns(&params, &mut rng);
+    /// let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+    ///
+    /// let ct = pt.encrypt_rns(&public_key, &mut rng);
+    /// ```
+    pub fn encrypt_rns<T: RngCore + CryptoRng>(
+        &self,
+        pub_key: &RnsPublicKey,
+        rng: &mut T,
+    ) -> RnsCiphertext {
+        self.try_encrypt_rns(pub_key, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Encrypt a plaintext with a public key as in `encrypt_rns`, returning an error instead
+    /// of panicking if the plaintext modulus or degree don't match the public key's parameters.
+    pub fn try_encrypt_rns<T: RngCore + CryptoRng>(
+        &self,
+        pub_key: &RnsPublicKey,
+        rng: &mut T,
+    ) -> Result<RnsCiphertext, Error> {
+        let params = &pub_key.params;
+        let (basis, degree) = (params.basis(), params.degree());
+        self.check_with(params.t(), degree)?;
+        let m = RnsPoly::from_poly(&(self.poly.clone() % (self.t, degree)), basis);
+
+        let u = params
+            .secret_distribution()
+            .randomness()
+            .sample(degree, rng);
+        let u = RnsPoly::from_poly(&u, basis);
+        let e_1 = RnsPoly::from_poly(&params.error_distribution().sample(degree, rng), basis);
+        let e_2 = RnsPoly::from_poly(&params.error_distribution().sample(degree, rng), basis);
+
+        let c_0 = pub_key.p_0.clone() * u.clone() + e_1 + m.mul_bigint(params.delta());
+        let c_1 = pub_key.p_1.clone() * u + e_2;
+
+        Ok(RnsCiphertext {
+            c_0,
+            c_1,
+            params: params.clone(),
+            key_id: pub_key.id,
+        })
     }
 }
@@ -1,7 +1,10 @@
+use super::big_poly::BigPoly;
+use super::ntt::{self, NttTables};
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use std::{cmp, fmt};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Poly(Vec<i64>);
 
 impl Add<Poly> for Poly {
@@ -95,23 +98,7 @@ impl Rem<(i64, usize)> for Poly {
     fn rem(self, modulus: (i64, usize)) -> Self::Output {
         let coeff_mod = modulus.0;
         let degree = modulus.1;
-        let mut out_val = vec![0; degree];
-
-        // Take the polynomial mod (X^N + 1).
-        // 1. After a multiplication by X^{2N}, the polynomial is unchanged mod (X^N + 1).
-        //    Therefore, we can take the degree % 2N.
-        // 2. If degree % 2N > N, the coefficients should be negated and added to the degree % N.
-        // 3. If degree % 2N <= N, the coefficients should be added to the degree % 2N.
-        for (i, coeff) in self.0.iter().enumerate() {
-            // $ X^i == X^{i + j * 2N} mod (X^N + 1) for all j $
-            // So we can take the coeff degree mod 2N.
-            let reduced_i = i % (2 * degree);
-            if reduced_i >= degree {
-                out_val[reduced_i % degree] -= coeff;
-            } else {
-                out_val[reduced_i] += coeff;
-            }
-        }
+        let mut out_val = self.fold(degree).0;
 
         // Take each coefficient % coeff_mod
         for coeff in out_val.iter_mut() {
@@ -158,6 +145,104 @@ impl Poly {
         (coeff % q + q) % q
     }
 
+    // Multiply each coefficient by num / den and round to the nearest integer, exactly.
+    // The products are computed with i128 integers, so unlike multiplying by the f64
+    // approximation of num / den, this doesn't lose precision for coefficients above 2^53.
+    // The result must fit in an i64, which holds for num <= den.
+    pub(crate) fn scale_round(&self, num: i64, den: i64) -> Poly {
+        let out_val = self
+            .0
+            .iter()
+            .map(|x| div_round(*x as i128 * num as i128, den as i128) as i64)
+            .collect();
+        Poly(out_val)
+    }
+
+    // Take the polynomial mod (X^N + 1), without reducing the coefficients.
+    fn fold(&self, degree: usize) -> Poly {
+        let mut out_val = vec![0; degree];
+
+        // 1. After a multiplication by X^{2N}, the polynomial is unchanged mod (X^N + 1).
+        //    Therefore, we can take the degree % 2N.
+        // 2. If degree % 2N > N, the coefficients should be negated and added to the degree % N.
+        // 3. If degree % 2N <= N, the coefficients should be added to the degree % 2N.
+        for (i, coeff) in self.0.iter().enumerate() {
+            // $ X^i == X^{i + j * 2N} mod (X^N + 1) for all j $
+            // So we can take the coeff degree mod 2N.
+            let reduced_i = i % (2 * degree);
+            if reduced_i >= degree {
+                out_val[reduced_i % degree] -= coeff;
+            } else {
+                out_val[reduced_i] += coeff;
+            }
+        }
+        Poly(out_val)
+    }
+
+    // Multiply two polynomials in R_q = Z_q[X] / (X^N + 1), with the output coefficients in [0, q).
+    // If q is NTT-friendly (a prime with q = 1 mod 2N), `tables` are its NTT tables for the
+    // degree, held by `Parameters`, and this uses the negacyclic NTT; otherwise it falls back to
+    // schoolbook multiplication.
+    pub(crate) fn mul_mod(
+        &self,
+        other: &Poly,
+        q: i64,
+        degree: usize,
+        tables: Option<&NttTables>,
+    ) -> Poly {
+        let a = self.clone() % (q, degree);
+        let b = other.clone() % (q, degree);
+        match tables {
+            Some(tables) => Poly(tables.negacyclic_mul(&a.0, &b.0)),
+            None => {
+                // Schoolbook multiplication, reducing each product mod q so it can't overflow.
+                let mut out_val = vec![0; degree];
+                for (i, a_i) in a.0.iter().enumerate() {
+                    for (j, b_j) in b.0.iter().enumerate() {
+                        let a_i_b_j = ntt::mul_mod(*a_i, *b_j, q);
+                        if i + j >= degree {
+                            out_val[i + j - degree] =
+                                ntt::sub_mod(out_val[i + j - degree], a_i_b_j, q);
+                        } else {
+                            out_val[i + j] = ntt::add_mod(out_val[i + j], a_i_b_j, q);
+                        }
+                    }
+                }
+                Poly(out_val)
+            }
+        }
+    }
+
+    // Multiply two polynomials in Z[X] / (X^N + 1) exactly, without reducing the coefficients.
+    // If the output coefficients are small enough, the product is computed with the NTT
+    // over an auxiliary 62-bit prime, otherwise it falls back to schoolbook multiplication
+    // over the big integers.
+    pub(crate) fn mul_negacyclic(&self, other: &Poly, degree: usize) -> BigPoly {
+        let a = self.fold(degree);
+        let b = other.fold(degree);
+        let a_max = a.0.iter().map(|a_i| a_i.unsigned_abs()).max().unwrap_or(0) as u128;
+        let b_max = b.0.iter().map(|b_i| b_i.unsigned_abs()).max().unwrap_or(0) as u128;
+        // Each output coefficient is a sum of N products, so it is bounded by N * |a|_inf * |b|_inf.
+        // The bound saturates rather than overflowing for 62-bit inputs and large degrees.
+        let bound = a_max.saturating_mul(b_max).saturating_mul(degree as u128);
+
+        match NttTables::aux(degree) {
+            Some(tables) if bound < tables.modulus() as u128 / 2 => {
+                let p = tables.modulus();
+                let a_p: Vec<i64> = a.0.iter().map(|a_i| a_i.rem_euclid(p)).collect();
+                let b_p: Vec<i64> = b.0.iter().map(|b_i| b_i.rem_euclid(p)).collect();
+                // Lift the output coefficients from [0, p) back to (-p/2, p/2).
+                let out_val = tables
+                    .negacyclic_mul(&a_p, &b_p)
+                    .into_iter()
+                    .map(|c| if c > p / 2 { c - p } else { c })
+                    .collect();
+                BigPoly::from(&Poly(out_val))
+            }
+            _ => (BigPoly::from(&a) * BigPoly::from(&b)).fold(degree),
+        }
+    }
+
     // Decompose a polynomial to l levels, with each level base T, such that:
     // $ poly = sum_{i=0}^l poly^(i) T^i $ with $ poly^(i) \in R_T $
     pub fn decompose(self, l: usize, base: i64) -> Vec<Poly> {
@@ -175,15 +260,11 @@ impl Poly {
                     .0
                     .iter_mut()
                     .map(|val_j| {
-                        // Calculate how many times T^i divides the coefficient, to get decomposition
-                        let fl_div = *val_j as f64 / base_i as f64;
-                        let int_div = if fl_div > 0.0 {
-                            fl_div.floor()
-                        } else {
-                            fl_div.ceil()
-                        } as i64;
+                        // Calculate how many times T^i divides the coefficient, to get decomposition.
+                        // Integer division rounds towards zero, and is exact for any coefficient size.
+                        let int_div = *val_j / base_i;
                         // Update the coefficient by subtracting T^i * the decomposed value
-                        *val_j = *val_j - base_i * int_div;
+                        *val_j -= base_i * int_div;
                         // Return the decomposed value for that coefficient for level i
                         int_div
                     })
@@ -197,9 +278,21 @@ impl Poly {
     }
 }
 
+/// Compute round(x / d) exactly, for d > 0, rounding halves up.
+pub(crate) fn div_round(x: i128, d: i128) -> i128 {
+    let (quotient, remainder) = (x.div_euclid(d), x.rem_euclid(d));
+    if 2 * remainder >= d {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ntt::NttTables;
     use crate::poly::Poly;
+    use num_bigint::BigInt;
 
     fn a_poly() -> Poly {
         Poly(vec![-7, 0, 0, 3, -1, 6, -3, 5, 9, -5])
@@ -285,6 +378,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mul_mod_test() {
+        let a = a_poly();
+        let b = b_poly();
+        // Without NTT tables, mul_mod falls back to schoolbook multiplication and reduction.
+        assert_eq!(
+            a.mul_mod(&b, 16, 8, None),
+            (a.clone() * b.clone()) % (16, 8)
+        );
+
+        // Cross-check the NTT against schoolbook multiplication, with q = 1 mod 2N.
+        let q = 12289;
+        for degree in vec![2, 4, 8, 16].into_iter() {
+            let tables = NttTables::get(q, degree);
+            assert!(tables.is_some());
+            assert_eq!(
+                a.mul_mod(&b, q, degree, tables.as_deref()),
+                (a.clone() * b.clone()) % (q, degree)
+            );
+        }
+    }
+
+    #[test]
+    fn mul_negacyclic_test() {
+        let a = a_poly();
+        let b = b_poly();
+        for degree in vec![2, 4, 8, 16].into_iter() {
+            assert_eq!(
+                a.mul_negacyclic(&b, degree).to_poly(),
+                (a.clone() * b.clone()).fold(degree)
+            );
+        }
+
+        // Coefficients too large for the auxiliary prime fall back to schoolbook multiplication.
+        let c = Poly(vec![1 << 40, -(1 << 40), 3, 1 << 30]);
+        let d = Poly(vec![1 << 20, 5, -(1 << 20), 7]);
+        assert_eq!(
+            c.mul_negacyclic(&d, 4).to_poly(),
+            (c.clone() * d.clone()).fold(4)
+        );
+
+        // Products which overflow an i64 are still computed exactly.
+        let e = Poly(vec![1 << 62, 1 << 62]);
+        let product = e.mul_negacyclic(&e, 2).val();
+        assert_eq!(product[0], BigInt::from(0));
+        assert_eq!(product[1], BigInt::from(1) << 125);
+    }
+
+    #[test]
+    fn scale_round_test() {
+        let a = Poly(vec![49, 24, 25, -26, -25, 0, 100]);
+        assert_eq!(a.scale_round(2, 100).0, vec![1, 0, 1, -1, 0, 0, 2]);
+    }
+
+    // Scaling by t/q in f64 is wrong once the coefficients exceed 2^53, which the exact
+    // integer scaling is not.
+    #[test]
+    fn scale_round_float_failure_test() {
+        let q = (1 << 61) - 1;
+        let t = 2;
+        // t * x / q = 1/2 - 3 / (2q), which rounds to 0.
+        let x = Poly(vec![(q - 3) / 4]);
+        assert_eq!(x.scale_round(t, q).0, vec![0]);
+
+        // As an f64, x is rounded up to 2^59, and t * x / q = 2^60 / (2^61 - 1) rounds to 1.
+        let delta_inv = t as f64 / q as f64;
+        assert_eq!((x * delta_inv).0, vec![1]);
+    }
+
     #[test]
     fn coeff_modulo_test() {
         let a = a_poly();
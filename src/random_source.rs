@@ -1,17 +1,313 @@
+use super::big_poly::BigPoly;
 use super::poly::Poly;
-use rand::distributions::{Distribution, Normal, Uniform};
-use rand::{CryptoRng, RngCore};
+use num_bigint::{BigInt, Sign};
+use rand::distributions::{Distribution, Uniform};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+use std::sync::OnceLock;
 
-/// Gets the discrete Gaussian distribution D_{Z, sigma} centered over 0, and take the absolute value.
+/// The discrete Gaussian is cut off at this many standard deviations from 0.
+/// The probability of the tail beyond it is below 2^-72, which is smaller than the
+/// 2^-64 precision of the cumulative distribution table.
+const GAUSSIAN_TAIL_CUT: f64 = 10.0;
+
+/// Samples the discrete Gaussian distribution D_{Z, sigma} centered over 0, using a
+/// cumulative distribution table (CDT).
+///
+/// `cdt[k]` holds `P(|x| <= k)` in 64-bit fixed point. To sample, a uniform 64-bit integer r is
+/// compared against every entry of the table, so that `|x|` is the number of entries below r,
+/// and a uniform sign is applied. The whole table is always scanned, and the sign is applied
+/// without branching, so the time taken doesn't depend on the sample.
+pub(crate) struct DiscreteGaussian {
+    cdt: Vec<u64>,
+}
+
+impl DiscreteGaussian {
+    pub(crate) fn new(std_dev: f64) -> DiscreteGaussian {
+        assert!(std_dev > 0.0);
+        let bound = (GAUSSIAN_TAIL_CUT * std_dev).ceil() as usize;
+
+        // P(x = k) is proportional to rho(k) = exp(-k^2 / (2 sigma^2)), and P(|x| = k) counts
+        // both k and -k for k > 0.
+        let weights: Vec<f64> = (0..=bound)
+            .map(|k| {
+                let rho = (-((k * k) as f64) / (2.0 * std_dev * std_dev)).exp();
+                if k == 0 {
+                    rho
+                } else {
+                    2.0 * rho
+                }
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        // Compute P(|x| > k) by summing from the tail, so that the small probabilities
+        // aren't lost to rounding, and store 2^64 - 2^64 * P(|x| > k) = 2^64 * P(|x| <= k).
+        let mut cdt = vec![0; bound];
+        let mut tail = 0.0;
+        for k in (0..bound).rev() {
+            tail += weights[k + 1] / total;
+            let tail_fixed = (tail * 2f64.powi(64)) as u128;
+            cdt[k] = ((1u128 << 64) - tail_fixed).min(u64::MAX as u128) as u64;
+        }
+        DiscreteGaussian { cdt }
+    }
+
+    pub(crate) fn sample<T: RngCore + CryptoRng>(&self, rng: &mut T) -> i64 {
+        let r = rng.next_u64();
+        let magnitude: i64 = self.cdt.iter().map(|c| (r >= *c) as i64).sum();
+        let sign = (rng.next_u32() & 1) as i64;
+        // magnitude if sign = 0, -magnitude if sign = 1
+        magnitude - 2 * sign * magnitude
+    }
+}
+
+/// The distribution of the coefficients of the secret key, and of the randomness u used in encryption.
+/// Only the key is sparse with a sparse distribution: u is then uniform ternary.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SecretDistribution {
+    /// Coefficients sampled uniformly from {0, 1}
+    Binary,
+    /// Coefficients sampled uniformly from {-1, 0, 1}, as assumed by the security standard
+    Ternary,
+    /// Exactly `hamming_weight` coefficients sampled uniformly from {-1, 1}, at uniformly random
+    /// positions, and the others 0. Sparse secrets reduce the noise growth of multiplication, but
+    /// the security standard doesn't cover them, and they are weaker against hybrid attacks.
+    SparseTernary { hamming_weight: usize },
+}
+
+impl SecretDistribution {
+    // The standard deviation of the coefficients of a secret of the given dimension, once centered.
+    // A binary secret s can be centered as s - 1/2, which has standard deviation 1/2.
+    pub(crate) fn std_dev(&self, dimension: usize) -> f64 {
+        match self {
+            SecretDistribution::Binary => 0.5,
+            SecretDistribution::Ternary => (2.0f64 / 3.0).sqrt(),
+            SecretDistribution::SparseTernary { hamming_weight } => {
+                (*hamming_weight as f64 / dimension as f64).sqrt()
+            }
+        }
+    }
+
+    // The largest number of nonzero coefficients of a secret of the given dimension.
+    pub(crate) fn hamming_weight(&self, dimension: usize) -> usize {
+        match self {
+            SecretDistribution::Binary | SecretDistribution::Ternary => dimension,
+            SecretDistribution::SparseTernary { hamming_weight } => *hamming_weight,
+        }
+    }
+
+    // The distribution of the randomness u used in public key encryption. A sparse u would weaken
+    // every encryption rather than just the key, so it is uniform ternary for sparse secrets.
+    pub(crate) fn randomness(&self) -> SecretDistribution {
+        match self {
+            SecretDistribution::SparseTernary { .. } => SecretDistribution::Ternary,
+            distribution => *distribution,
+        }
+    }
+
+    pub(crate) fn sample<T: RngCore + CryptoRng>(&self, dimension: usize, rng: &mut T) -> Poly {
+        match self {
+            SecretDistribution::Binary => get_uniform(2, dimension, rng),
+            SecretDistribution::Ternary => get_ternary(dimension, rng),
+            SecretDistribution::SparseTernary { hamming_weight } => {
+                get_sparse_ternary(*hamming_weight, dimension, rng)
+            }
+        }
+    }
+}
+
+/// The distribution of the error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorDistribution {
+    /// The discrete Gaussian distribution D_{Z, sigma} centered over 0, with sigma = `std_dev`
+    Gaussian { std_dev: f64 },
+    /// The centered binomial distribution `sum_{i=1}^eta (a_i - b_i)` for uniform bits a_i, b_i,
+    /// which has standard deviation `sqrt(eta / 2)`. `eta` must be in [1, 32].
+    CenteredBinomial { eta: u32 },
+}
+
+impl ErrorDistribution {
+    /// The standard deviation of the error
+    pub fn std_dev(&self) -> f64 {
+        match self {
+            ErrorDistribution::Gaussian { std_dev } => *std_dev,
+            ErrorDistribution::CenteredBinomial { eta } => (*eta as f64 / 2.0).sqrt(),
+        }
+    }
+
+    // The largest absolute value of a sample.
+    pub(crate) fn bound(&self) -> f64 {
+        match self {
+            ErrorDistribution::Gaussian { std_dev } => (GAUSSIAN_TAIL_CUT * std_dev).ceil(),
+            ErrorDistribution::CenteredBinomial { eta } => *eta as f64,
+        }
+    }
+
+    pub(crate) fn sample<T: RngCore + CryptoRng>(&self, dimension: usize, rng: &mut T) -> Poly {
+        match self {
+            ErrorDistribution::Gaussian { std_dev } => get_gaussian(*std_dev, dimension, rng),
+            ErrorDistribution::CenteredBinomial { eta } => {
+                get_centered_binomial(*eta, dimension, rng)
+            }
+        }
+    }
+}
+
+/// A 32-byte seed, from which uniformly random values can be regenerated.
+pub(crate) type Seed = [u8; 32];
+
+/// Sample a fresh seed.
+pub(crate) fn get_seed<T: RngCore + CryptoRng>(rng: &mut T) -> Seed {
+    let mut seed = [0; 32];
+    rng.fill_bytes(&mut seed);
+    seed
+}
+
+/// A uniformly random value, which is either stored, or stored as the seed it was sampled from
+/// and regenerated with ChaCha20 the first time it is used. Storing just the seed makes keys and
+/// ciphertexts about half the size when they are serialized.
+#[derive(Clone, Debug)]
+pub(crate) struct Seeded<T> {
+    seed: Option<Seed>,
+    value: OnceLock<T>,
+}
+
+impl<T> Seeded<T> {
+    /// A value that is stored, without a seed.
+    pub(crate) fn from_value(value: T) -> Seeded<T> {
+        Seeded {
+            seed: None,
+            value: OnceLock::from(value),
+        }
+    }
+
+    /// A value that is regenerated from the seed with `get`.
+    pub(crate) fn from_seed(seed: Seed) -> Seeded<T> {
+        Seeded {
+            seed: Some(seed),
+            value: OnceLock::new(),
+        }
+    }
+
+    /// The seed, if the value was sampled from one.
+    pub(crate) fn seed(&self) -> Option<&Seed> {
+        self.seed.as_ref()
+    }
+
+    /// The value, which is sampled by `expand` from the seeded RNG if it isn't stored yet.
+    /// `expand` must always sample the same distribution for a given value.
+    pub(crate) fn get<F: FnOnce(&mut ChaChaRng) -> T>(&self, expand: F) -> &T {
+        self.value.get_or_init(|| {
+            let seed = self.seed.expect("a value without a seed is always stored");
+            expand(&mut ChaChaRng::from_seed(seed))
+        })
+    }
+}
+
+impl Seeded<Poly> {
+    /// Sample a polynomial uniformly over [0, q), from a fresh seed if `seeded`.
+    pub(crate) fn uniform<T: RngCore + CryptoRng>(
+        q: i64,
+        degree: usize,
+        seeded: bool,
+        rng: &mut T,
+    ) -> Seeded<Poly> {
+        if seeded {
+            Seeded::from_seed(get_seed(rng))
+        } else {
+            Seeded::from_value(get_uniform(q, degree, rng))
+        }
+    }
+
+    /// The polynomial sampled by `uniform`.
+    pub(crate) fn get_uniform(&self, q: i64, degree: usize) -> &Poly {
+        self.get(|rng| get_uniform(q, degree, rng))
+    }
+}
+
+impl Seeded<BigPoly> {
+    /// Sample a polynomial with big integer coefficients uniformly over [0, bound), from a fresh
+    /// seed if `seeded`.
+    pub(crate) fn uniform_bigint<T: RngCore + CryptoRng>(
+        bound: &BigInt,
+        degree: usize,
+        seeded: bool,
+        rng: &mut T,
+    ) -> Seeded<BigPoly> {
+        if seeded {
+            Seeded::from_seed(get_seed(rng))
+        } else {
+            Seeded::from_value(get_uniform_bigint(bound, degree, rng))
+        }
+    }
+
+    /// The polynomial sampled by `uniform_bigint`.
+    pub(crate) fn get_uniform_bigint(&self, bound: &BigInt, degree: usize) -> &BigPoly {
+        self.get(|rng| get_uniform_bigint(bound, degree, rng))
+    }
+}
+
+/// Samples the discrete Gaussian distribution D_{Z, sigma} centered over 0, with sigma = std_dev.
 /// Returns a dimension-length vector of gaussian samples.
 pub fn get_gaussian<T: RngCore + CryptoRng>(std_dev: f64, dimension: usize, rng: &mut T) -> Poly {
-    let gaussian = Normal::new(0.0, std_dev);
+    let gaussian = DiscreteGaussian::new(std_dev);
+    let val: Vec<i64> = (0..dimension).map(|_| gaussian.sample(rng)).collect();
+    Poly::new(val)
+}
+
+/// Samples the centered binomial distribution with parameter eta, for eta in [1, 32].
+/// Returns a dimension-length vector of samples in [-eta, eta].
+pub fn get_centered_binomial<T: RngCore + CryptoRng>(
+    eta: u32,
+    dimension: usize,
+    rng: &mut T,
+) -> Poly {
+    assert!((1..=32).contains(&eta));
+    let mask = (1u64 << eta) - 1;
     let val: Vec<i64> = (0..dimension)
-        .map(|_| gaussian.sample(rng).abs() as i64)
+        .map(|_| {
+            // Use the low bits as the a_i, and the high bits as the b_i.
+            let bits = rng.next_u64();
+            let a = (bits & mask).count_ones() as i64;
+            let b = ((bits >> 32) & mask).count_ones() as i64;
+            a - b
+        })
         .collect();
     Poly::new(val)
 }
 
+/// Returns a dimension-length vector of values sampled uniformly from {-1, 0, 1}.
+pub fn get_ternary<T: RngCore + CryptoRng>(dimension: usize, rng: &mut T) -> Poly {
+    let between = Uniform::new_inclusive(-1, 1);
+
+    let val: Vec<i64> = (0..dimension).map(|_| between.sample(rng)).collect();
+    Poly::new(val)
+}
+
+/// Returns a dimension-length vector with exactly hamming_weight values sampled uniformly
+/// from {-1, 1}, at uniformly random positions, and the others 0.
+pub fn get_sparse_ternary<T: RngCore + CryptoRng>(
+    hamming_weight: usize,
+    dimension: usize,
+    rng: &mut T,
+) -> Poly {
+    assert!(hamming_weight <= dimension);
+    // A partial Fisher-Yates shuffle, after which the first hamming_weight positions are a
+    // uniformly random subset of [0, dimension).
+    let mut positions: Vec<usize> = (0..dimension).collect();
+    for i in 0..hamming_weight {
+        let j = Uniform::new(i, dimension).sample(rng);
+        positions.swap(i, j);
+    }
+
+    let mut val = vec![0; dimension];
+    for i in positions.iter().take(hamming_weight) {
+        val[*i] = 1 - 2 * (rng.next_u32() & 1) as i64;
+    }
+    Poly::new(val)
+}
+
 /// Returns a dimension-length vector of values sampled uniformly from [0, bound).
 pub fn get_uniform<T: RngCore + CryptoRng>(bound: i64, dimension: usize, rng: &mut T) -> Poly {
     let between = Uniform::new(0, bound);
@@ -19,3 +315,176 @@ pub fn get_uniform<T: RngCore + CryptoRng>(bound: i64, dimension: usize, rng: &m
     let val: Vec<i64> = (0..dimension).map(|_| between.sample(rng)).collect();
     Poly::new(val)
 }
+
+/// Returns a dimension-length vector of big integers sampled uniformly from [0, bound).
+pub fn get_uniform_bigint<T: RngCore + CryptoRng>(
+    bound: &BigInt,
+    dimension: usize,
+    rng: &mut T,
+) -> BigPoly {
+    let bits = bound.bits();
+    let bytes = bits.div_ceil(8) as usize;
+    let excess_bits = bytes as u64 * 8 - bits;
+
+    let val = (0..dimension)
+        .map(|_| {
+            // Rejection sampling: sample `bits` random bits until the result is below the bound.
+            let mut buf = vec![0u8; bytes];
+            loop {
+                rng.fill_bytes(&mut buf);
+                buf[0] >>= excess_bits;
+                let sample = BigInt::from_bytes_be(Sign::Plus, &buf);
+                if &sample < bound {
+                    return sample;
+                }
+            }
+        })
+        .collect();
+    BigPoly::new(val)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    // The sample mean and variance of a vector of samples.
+    fn mean_variance(samples: &[i64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<i64>() as f64 / n;
+        let variance = samples
+            .iter()
+            .map(|x| (*x as f64 - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1.0);
+        (mean, variance)
+    }
+
+    #[test]
+    fn gaussian_mean_variance_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let n = 100_000;
+        for std_dev in [1.0, 3.2, 8.0].iter() {
+            let samples = get_gaussian(*std_dev, n, &mut rng).val();
+            let (mean, variance) = mean_variance(&samples);
+            let expected_variance = std_dev * std_dev;
+
+            // The sample mean has standard deviation sigma / sqrt(n), and the sample variance
+            // has standard deviation about sigma^2 * sqrt(2 / n); allow 5 of each.
+            assert!(
+                mean.abs() < 5.0 * std_dev / (n as f64).sqrt(),
+                "mean {} for sigma {}",
+                mean,
+                std_dev
+            );
+            assert!(
+                (variance - expected_variance).abs()
+                    < 5.0 * expected_variance * (2.0 / n as f64).sqrt(),
+                "variance {} for sigma {}",
+                variance,
+                std_dev
+            );
+
+            // Samples are symmetric around 0, and within the tail cut.
+            let negative = samples.iter().filter(|x| **x < 0).count();
+            let positive = samples.iter().filter(|x| **x > 0).count();
+            assert!((negative as f64 - positive as f64).abs() < 5.0 * (n as f64).sqrt());
+            let bound = (GAUSSIAN_TAIL_CUT * std_dev).ceil() as i64;
+            assert!(samples.iter().all(|x| x.abs() <= bound));
+        }
+    }
+
+    #[test]
+    fn centered_binomial_mean_variance_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+        let n = 100_000;
+        for eta in [1, 2, 8, 21, 32].iter() {
+            let samples = get_centered_binomial(*eta, n, &mut rng).val();
+            let (mean, variance) = mean_variance(&samples);
+            let expected_variance = *eta as f64 / 2.0;
+
+            assert!(mean.abs() < 5.0 * expected_variance.sqrt() / (n as f64).sqrt());
+            assert!(
+                (variance - expected_variance).abs()
+                    < 5.0 * expected_variance * (2.0 / n as f64).sqrt(),
+                "variance {} for eta {}",
+                variance,
+                eta
+            );
+            assert!(samples.iter().all(|x| x.abs() <= *eta as i64));
+        }
+    }
+
+    #[test]
+    fn ternary_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(20);
+        let n = 30_000;
+        let samples = get_ternary(n, &mut rng).val();
+        for value in -1..=1 {
+            let count = samples.iter().filter(|x| **x == value).count() as f64;
+            // Each count is binomial(n, 1/3), with standard deviation sqrt(2n/9)
+            assert!((count - n as f64 / 3.0).abs() < 5.0 * (2.0 * n as f64 / 9.0).sqrt());
+        }
+        let (_, variance) = mean_variance(&samples);
+        let std_dev = SecretDistribution::Ternary.std_dev(n);
+        assert!((variance - std_dev * std_dev).abs() < 0.02);
+    }
+
+    #[test]
+    fn sparse_ternary_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+        let (n, h) = (1024, 64);
+        let mut position_counts = vec![0; n];
+        for _ in 0..100 {
+            let samples = get_sparse_ternary(h, n, &mut rng).val();
+            assert_eq!(samples.iter().filter(|x| **x != 0).count(), h);
+            assert!(samples.iter().all(|x| x.abs() <= 1));
+            for (i, x) in samples.iter().enumerate() {
+                position_counts[i] += x.abs();
+            }
+        }
+        // Every position is nonzero with probability h / n, so is hit 6.25 times on average.
+        assert!(position_counts.iter().all(|c| *c < 25));
+        assert!(position_counts.iter().filter(|c| **c == 0).count() < 20);
+
+        let sparse = SecretDistribution::SparseTernary { hamming_weight: h };
+        assert_eq!(sparse.std_dev(n), 0.25);
+
+        // Only the secret key is sparse, not the encryption randomness
+        assert_eq!(sparse.randomness(), SecretDistribution::Ternary);
+        assert_eq!(
+            SecretDistribution::Binary.randomness(),
+            SecretDistribution::Binary
+        );
+    }
+
+    #[test]
+    fn seeded_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(22);
+        let seed = get_seed(&mut rng);
+        let expand = |rng: &mut ChaChaRng| get_uniform(65536, 16, rng);
+
+        // The same seed always expands to the same value
+        let seeded: Seeded<Poly> = Seeded::from_seed(seed);
+        let value = seeded.get(expand).clone();
+        assert_eq!(seeded.get_uniform(65536, 16), &value);
+        assert_eq!(seeded.clone().get(expand), &value);
+        assert_eq!(Seeded::from_seed(seed).get(expand), &value);
+        assert_ne!(Seeded::from_seed(get_seed(&mut rng)).get(expand), &value);
+        assert_eq!(seeded.seed(), Some(&seed));
+
+        let stored = Seeded::from_value(value.clone());
+        assert_eq!(stored.seed(), None);
+        assert_eq!(stored.get(|_| unreachable!()), &value);
+    }
+
+    #[test]
+    fn gaussian_table_test() {
+        let gaussian = DiscreteGaussian::new(3.2);
+        assert_eq!(gaussian.cdt.len(), 32);
+        // The table is increasing, and P(x = 0) = 1 / sum_k rho(k) ~ 1 / (sqrt(2 pi) * sigma)
+        assert!(gaussian.cdt.windows(2).all(|w| w[0] <= w[1]));
+        let p_0 = gaussian.cdt[0] as f64 / 2f64.powi(64);
+        assert!((p_0 - 1.0 / ((2.0 * std::f64::consts::PI).sqrt() * 3.2)).abs() < 1e-6);
+    }
+}
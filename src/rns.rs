@@ -0,0 +1,557 @@
+use super::big_poly::BigPoly;
+use super::ntt::{self, NttTables};
+use super::params::{ParametersError, SecurityLevel};
+use super::poly::Poly;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::ToPrimitive;
+use rand::distributions::{Distribution, Uniform};
+use rand::{CryptoRng, RngCore};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::sync::{Arc, OnceLock};
+
+/// A Residue Number System (RNS) basis for the ciphertext modulus.
+///
+/// The modulus `q = q_0 * q_1 * ... * q_{k-1}` is represented by a chain of distinct
+/// NTT-friendly primes that each fit in a machine word, so that q can be hundreds of bits
+/// while all polynomial arithmetic is done on `i64` residues.
+#[derive(Debug)]
+pub struct RnsBasis {
+    moduli: Vec<i64>,
+    degree: usize,
+    tables: Vec<Arc<NttTables>>,
+    /// q = prod q_i
+    q: BigInt,
+    /// q_i^* = q / q_i
+    q_star: Vec<BigInt>,
+    /// q_i~ = (q / q_i)^{-1} mod q_i
+    q_tilde: Vec<i64>,
+    /// The larger basis used to compute exact tensor products during multiplication.
+    tensor_basis: OnceLock<Arc<RnsBasis>>,
+}
+
+impl RnsBasis {
+    /// Create an RNS basis from a list of distinct primes, each of which must be
+    /// NTT-friendly for `degree` (q_i = 1 mod 2 * degree).
+    ///
+    /// * `moduli`: the primes q_i, whose product is the ciphertext modulus
+    /// * `degree`: the polynomial degree N, which must be a power of two
+    ///
+    /// ```rust
+    /// use bfv12::RnsBasis;
+    /// let basis = RnsBasis::new(vec![12289, 40961], 1024);
+    /// assert_eq!(basis.modulus_bits(), 29);
+    /// ```
+    pub fn new(moduli: Vec<i64>, degree: usize) -> Arc<RnsBasis> {
+        assert!(!moduli.is_empty());
+        let tables: Vec<Arc<NttTables>> = moduli
+            .iter()
+            .map(|q_i| NttTables::get(*q_i, degree).expect("RNS moduli must be NTT-friendly"))
+            .collect();
+        for (i, q_i) in moduli.iter().enumerate() {
+            assert!(!moduli[..i].contains(q_i), "RNS moduli must be distinct");
+        }
+
+        let q: BigInt = moduli.iter().map(|q_i| BigInt::from(*q_i)).product();
+        let q_star: Vec<BigInt> = moduli.iter().map(|q_i| &q / q_i).collect();
+        let q_tilde = moduli
+            .iter()
+            .zip(q_star.iter())
+            .map(|(q_i, q_star_i)| ntt::inv_mod(bigint_mod(q_star_i, *q_i), *q_i))
+            .collect();
+
+        Arc::new(RnsBasis {
+            moduli,
+            degree,
+            tables,
+            q,
+            q_star,
+            q_tilde,
+            tensor_basis: OnceLock::new(),
+        })
+    }
+
+    /// Generate an RNS basis of `count` distinct NTT-friendly primes, each smaller than 2^bits.
+    ///
+    /// * `count`: the number of primes in the basis
+    /// * `bits`: the bit size of each prime, at most 62
+    /// * `degree`: the polynomial degree N, which must be a power of two
+    ///
+    /// ```rust
+    /// use bfv12::RnsBasis;
+    /// // A 150-bit ciphertext modulus
+    /// let basis = RnsBasis::generate(3, 50, 1024);
+    /// assert_eq!(basis.modulus_bits(), 150);
+    /// ```
+    pub fn generate(count: usize, bits: u32, degree: usize) -> Arc<RnsBasis> {
+        assert!(bits <= 62);
+        RnsBasis::new(ntt_primes(count, 1 << bits, degree, &[]), degree)
+    }
+
+    /// Generate a standard RNS basis, from the HomomorphicEncryption.org security standard.
+    ///
+    /// The ciphertext modulus is the product of the fewest NTT-friendly primes of at most 60 bits
+    /// needed to reach the largest modulus allowed for the degree and security level.
+    ///
+    /// * `degree`: the degree N, one of 1024, 2048, 4096, 8192, 16384 or 32768
+    /// * `level`: the security level
+    ///
+    /// ```rust
+    /// use bfv12::{RnsBasis, SecurityLevel};
+    /// // At most a 109-bit ciphertext modulus, represented by two 54-bit primes
+    /// let basis = RnsBasis::standard(4096, SecurityLevel::Bits128).unwrap();
+    /// assert_eq!(basis.modulus_bits(), 108);
+    /// assert_eq!(basis.security_level(), Some(SecurityLevel::Bits128));
+    /// ```
+    pub fn standard(degree: usize, level: SecurityLevel) -> Result<Arc<RnsBasis>, ParametersError> {
+        let max_bits = level
+            .max_modulus_bits(degree)
+            .ok_or(ParametersError::NonStandardDegree(degree))?;
+        let count = max_bits.div_ceil(60);
+        Ok(RnsBasis::generate(count as usize, max_bits / count, degree))
+    }
+
+    /// The primes q_i of the basis.
+    pub fn moduli(&self) -> &[i64] {
+        &self.moduli
+    }
+
+    /// The polynomial degree N.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// The number of bits in the ciphertext modulus q = prod q_i.
+    pub fn modulus_bits(&self) -> u64 {
+        self.q.bits()
+    }
+
+    /// The security level claimed for this basis (and its degree) by the HomomorphicEncryption.org
+    /// standard, or `None` if it doesn't reach 128-bit security. This assumes the secret and error
+    /// distributions of the standard: a uniform ternary secret and standard deviation 3.2.
+    pub fn security_level(&self) -> Option<SecurityLevel> {
+        SecurityLevel::of(self.degree, self.modulus_bits())
+    }
+
+    pub(crate) fn modulus(&self) -> &BigInt {
+        &self.q
+    }
+
+    // The basis used to compute exact products of polynomials with coefficients in (-q/2, q/2].
+    // The product coefficients are bounded by N * q^2 / 4, so the tensor basis is chosen to be
+    // larger than N * q^2 (with room to add two products), using 61-bit primes not in this basis.
+    pub(crate) fn tensor_basis(&self) -> &Arc<RnsBasis> {
+        self.tensor_basis.get_or_init(|| {
+            let bound_bits = 2 * self.q.bits() + self.degree.trailing_zeros() as u64 + 2;
+            let count = bound_bits.div_ceil(60) as usize;
+            RnsBasis::new(
+                ntt_primes(count, 1 << 61, self.degree, &self.moduli),
+                self.degree,
+            )
+        })
+    }
+}
+
+// Find the `count` largest NTT-friendly primes below `upper` which are not in `exclude`.
+fn ntt_primes(count: usize, upper: i64, degree: usize, exclude: &[i64]) -> Vec<i64> {
+    let mut primes = Vec::with_capacity(count);
+    let mut upper = upper;
+    while primes.len() < count {
+        let prime = ntt::previous_ntt_prime(upper, degree).expect("not enough NTT-friendly primes");
+        if !exclude.contains(&prime) {
+            primes.push(prime);
+        }
+        upper = prime;
+    }
+    primes
+}
+
+// Reduce a big integer into [0, q).
+fn bigint_mod(x: &BigInt, q: i64) -> i64 {
+    x.mod_floor(&BigInt::from(q)).to_i64().unwrap()
+}
+
+/// A polynomial in R_q, with q given by an RNS basis.
+///
+/// Each coefficient is stored as its residues modulo the primes q_i of the basis:
+/// `residues[i][j]` is the j-th coefficient modulo q_i.
+#[derive(Clone, Debug)]
+pub struct RnsPoly {
+    basis: Arc<RnsBasis>,
+    residues: Vec<Vec<i64>>,
+}
+
+impl PartialEq for RnsPoly {
+    fn eq(&self, other: &RnsPoly) -> bool {
+        self.basis.moduli == other.basis.moduli && self.residues == other.residues
+    }
+}
+
+impl RnsPoly {
+    pub(crate) fn zero(basis: &Arc<RnsBasis>) -> RnsPoly {
+        RnsPoly {
+            basis: basis.clone(),
+            residues: vec![vec![0; basis.degree]; basis.moduli.len()],
+        }
+    }
+
+    /// Represent a polynomial with (small, possibly negative) i64 coefficients in the RNS basis.
+    pub(crate) fn from_poly(poly: &Poly, basis: &Arc<RnsBasis>) -> RnsPoly {
+        let residues = basis
+            .moduli
+            .iter()
+            .map(|q_i| (poly.clone() % (*q_i, basis.degree)).val())
+            .collect();
+        RnsPoly {
+            basis: basis.clone(),
+            residues,
+        }
+    }
+
+    /// Represent a polynomial with big integer coefficients in the RNS basis.
+    pub(crate) fn from_bigint(coeffs: &[BigInt], basis: &Arc<RnsBasis>) -> RnsPoly {
+        assert_eq!(coeffs.len(), basis.degree);
+        let residues = basis
+            .moduli
+            .iter()
+            .map(|q_i| coeffs.iter().map(|c| bigint_mod(c, *q_i)).collect())
+            .collect();
+        RnsPoly {
+            basis: basis.clone(),
+            residues,
+        }
+    }
+
+    /// Sample a polynomial with coefficients uniform over [0, q).
+    /// By the CRT, this is the same as sampling each residue uniformly over [0, q_i).
+    pub(crate) fn uniform<T: RngCore + CryptoRng>(basis: &Arc<RnsBasis>, rng: &mut T) -> RnsPoly {
+        let residues = basis
+            .moduli
+            .iter()
+            .map(|q_i| {
+                let between = Uniform::new(0, *q_i);
+                (0..basis.degree).map(|_| between.sample(rng)).collect()
+            })
+            .collect();
+        RnsPoly {
+            basis: basis.clone(),
+            residues,
+        }
+    }
+
+    /// Represent a polynomial given by its residues, with `residues[i]` in [0, q_i) for each q_i.
+    pub(crate) fn from_residues(residues: Vec<Vec<i64>>, basis: &Arc<RnsBasis>) -> RnsPoly {
+        assert_eq!(residues.len(), basis.moduli.len());
+        RnsPoly {
+            basis: basis.clone(),
+            residues,
+        }
+    }
+
+    pub(crate) fn basis(&self) -> &Arc<RnsBasis> {
+        &self.basis
+    }
+
+    pub(crate) fn residues(&self) -> &[Vec<i64>] {
+        &self.residues
+    }
+
+    /// Reconstruct the coefficients in [0, q) using the CRT:
+    /// `x = sum_i [x_i * q_i~]_{q_i} * q_i^* mod q`
+    pub(crate) fn to_bigint(&self) -> Vec<BigInt> {
+        let basis = &self.basis;
+        (0..basis.degree)
+            .map(|j| {
+                let x: BigInt = (0..basis.moduli.len())
+                    .map(|i| {
+                        let x_i =
+                            ntt::mul_mod(self.residues[i][j], basis.q_tilde[i], basis.moduli[i]);
+                        &basis.q_star[i] * x_i
+                    })
+                    .sum();
+                x % &basis.q
+            })
+            .collect()
+    }
+
+    /// Reconstruct the coefficients in (-q/2, q/2].
+    pub(crate) fn to_centered_bigint(&self) -> Vec<BigInt> {
+        let q = &self.basis.q;
+        let half_q: BigInt = q / 2;
+        self.to_bigint()
+            .into_iter()
+            .map(|x| if x > half_q { x - q } else { x })
+            .collect()
+    }
+
+    /// Represent the same polynomial, with coefficients lifted to (-q/2, q/2], in the tensor basis.
+    pub(crate) fn extend(&self) -> RnsPoly {
+        RnsPoly::from_bigint(&self.to_centered_bigint(), self.basis.tensor_basis())
+    }
+
+    /// Compute `[round(t/q * x)]_q`, where x is this polynomial lifted to (-Q/2, Q/2] for its
+    /// own (tensor) basis Q, and q is the modulus of the target basis.
+    pub(crate) fn scale_round(&self, t: i64, target: &Arc<RnsBasis>) -> RnsPoly {
+        let scaled =
+            BigPoly::new(self.to_centered_bigint()).scale_round(&BigInt::from(t), &target.q);
+        RnsPoly::from_bigint(&scaled.val(), target)
+    }
+
+    /// Compute `[round(t/q * x)]_t` for x in [0, q), without reconstructing x with the CRT.
+    ///
+    /// This follows the approach of Halevi, Polyakov and Shoup (HPS): since
+    /// $ t * x / q = sum_i x_i * (t * q_i~ / q_i) mod t $, each term can be split into an integer
+    /// part, which is summed mod t, and a fractional part in [0, 1), which is summed in 64-bit
+    /// fixed point. The fixed point error is at most k / 2^64, so the rounding is exact unless
+    /// t * x / q is within k / 2^64 of a half-integer, which a decryptable ciphertext never is.
+    pub(crate) fn scale_round_mod_t(&self, t: i64) -> Poly {
+        let basis = &self.basis;
+        // For each q_i, t * q_i~ = omega_i * q_i + rho_i, with rho_i in [0, q_i)
+        let (omega, rho): (Vec<i128>, Vec<u128>) = basis
+            .moduli
+            .iter()
+            .zip(basis.q_tilde.iter())
+            .map(|(q_i, q_tilde_i)| {
+                let t_q_tilde_i = t as i128 * *q_tilde_i as i128;
+                (
+                    t_q_tilde_i / *q_i as i128,
+                    (t_q_tilde_i % *q_i as i128) as u128,
+                )
+            })
+            .unzip();
+
+        let val = (0..basis.degree)
+            .map(|j| {
+                let mut integer_part: i128 = 0;
+                let mut fractional_part: u128 = 0;
+                for (i, q_i) in basis.moduli.iter().enumerate() {
+                    let x_i = self.residues[i][j] as u128;
+                    let q_i = *q_i as u128;
+                    // x_i * rho_i / q_i = a + b / q_i
+                    let x_i_rho_i = x_i * rho[i];
+                    let (a, b) = (x_i_rho_i / q_i, x_i_rho_i % q_i);
+                    integer_part = (integer_part + x_i as i128 * omega[i] + a as i128) % t as i128;
+                    // b / q_i in fixed point, with 64 fractional bits
+                    fractional_part += (b << 64) / q_i;
+                }
+                let rounded = ((fractional_part + (1 << 63)) >> 64) as i128;
+                ((integer_part + rounded) % t as i128) as i64
+            })
+            .collect();
+        Poly::new(val)
+    }
+
+    /// Multiply by a big integer constant.
+    pub(crate) fn mul_bigint(&self, c: &BigInt) -> RnsPoly {
+        let residues = self
+            .basis
+            .moduli
+            .iter()
+            .zip(self.residues.iter())
+            .map(|(q_i, x_i)| {
+                let c_i = bigint_mod(c, *q_i);
+                x_i.iter().map(|x| ntt::mul_mod(*x, c_i, *q_i)).collect()
+            })
+            .collect();
+        RnsPoly {
+            basis: self.basis.clone(),
+            residues,
+        }
+    }
+
+    /// Decompose a polynomial into its RNS digits, such that:
+    /// $ x = sum_i [x * q_i~]_{q_i} * q_i^* mod q $
+    /// Each digit has coefficients in [0, q_i), and is returned represented in the full basis.
+    pub(crate) fn decompose(&self) -> Vec<RnsPoly> {
+        let basis = &self.basis;
+        (0..basis.moduli.len())
+            .map(|i| {
+                let digit: Vec<i64> = self.residues[i]
+                    .iter()
+                    .map(|x| ntt::mul_mod(*x, basis.q_tilde[i], basis.moduli[i]))
+                    .collect();
+                RnsPoly::from_poly(&Poly::new(digit), basis)
+            })
+            .collect()
+    }
+
+    /// The gadget vector for the RNS decomposition: `[q_i^* : i in (0..k)]`
+    pub(crate) fn gadget(basis: &RnsBasis) -> &[BigInt] {
+        &basis.q_star
+    }
+
+    fn zip_with<F: Fn(i64, i64, i64) -> i64>(self, other: RnsPoly, f: F) -> RnsPoly {
+        assert_eq!(self.basis.moduli, other.basis.moduli);
+        let residues = self
+            .basis
+            .moduli
+            .iter()
+            .zip(self.residues.iter().zip(other.residues.iter()))
+            .map(|(q_i, (x_i, y_i))| {
+                x_i.iter()
+                    .zip(y_i.iter())
+                    .map(|(x, y)| f(*x, *y, *q_i))
+                    .collect()
+            })
+            .collect();
+        RnsPoly {
+            basis: self.basis,
+            residues,
+        }
+    }
+}
+
+impl Add<RnsPoly> for RnsPoly {
+    type Output = RnsPoly;
+    fn add(self, other: RnsPoly) -> Self::Output {
+        self.zip_with(other, ntt::add_mod)
+    }
+}
+
+impl Sub<RnsPoly> for RnsPoly {
+    type Output = RnsPoly;
+    fn sub(self, other: RnsPoly) -> Self::Output {
+        self.zip_with(other, ntt::sub_mod)
+    }
+}
+
+impl Neg for RnsPoly {
+    type Output = RnsPoly;
+    fn neg(self) -> Self::Output {
+        RnsPoly::zero(&self.basis.clone()) - self
+    }
+}
+
+// Multiply in R_q, using the NTT for each prime q_i.
+impl Mul<RnsPoly> for RnsPoly {
+    type Output = RnsPoly;
+    fn mul(self, other: RnsPoly) -> Self::Output {
+        assert_eq!(self.basis.moduli, other.basis.moduli);
+        let residues = self
+            .basis
+            .tables
+            .iter()
+            .zip(self.residues.iter().zip(other.residues.iter()))
+            .map(|(tables, (x_i, y_i))| tables.negacyclic_mul(x_i, y_i))
+            .collect();
+        RnsPoly {
+            basis: self.basis,
+            residues,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Zero;
+    use rand::SeedableRng;
+
+    fn bigint_poly(val: Vec<i64>) -> Vec<BigInt> {
+        val.into_iter().map(BigInt::from).collect()
+    }
+
+    #[test]
+    fn crt_roundtrip_test() {
+        let basis = RnsBasis::generate(3, 50, 8);
+        assert_eq!(basis.modulus_bits(), 150);
+
+        let q = basis.modulus().clone();
+        let coeffs: Vec<BigInt> = (0..8).map(|i| &q / (i + 2) + i).collect();
+        let poly = RnsPoly::from_bigint(&coeffs, &basis);
+        assert_eq!(poly.to_bigint(), coeffs);
+
+        let small = RnsPoly::from_poly(&Poly::new(vec![-3, 2, -1, 0, 5, -8, 13, -21]), &basis);
+        assert_eq!(
+            small.to_centered_bigint(),
+            bigint_poly(vec![-3, 2, -1, 0, 5, -8, 13, -21])
+        );
+    }
+
+    #[test]
+    fn standard_basis_test() {
+        for degree in [1024, 2048, 4096, 8192].iter() {
+            for level in [SecurityLevel::Bits128, SecurityLevel::Bits256].iter() {
+                let basis = RnsBasis::standard(*degree, *level).unwrap();
+                let max_bits = level.max_modulus_bits(*degree).unwrap() as u64;
+                assert!(basis.modulus_bits() <= max_bits);
+                assert!(basis.modulus_bits() + 4 > max_bits);
+                assert_eq!(basis.security_level(), Some(*level));
+            }
+        }
+        assert!(RnsBasis::standard(8, SecurityLevel::Bits128).is_err());
+    }
+
+    #[test]
+    fn rns_arithmetic_test() {
+        let degree = 16;
+        let basis = RnsBasis::generate(4, 40, degree);
+        let q = basis.modulus().clone();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(24);
+
+        let a = RnsPoly::uniform(&basis, &mut rng);
+        let b = RnsPoly::uniform(&basis, &mut rng);
+        let a_big = a.to_bigint();
+        let b_big = b.to_bigint();
+
+        // Schoolbook multiplication over the big integers, as a reference for the NTT.
+        let mut expected_mul = vec![BigInt::zero(); degree];
+        for i in 0..degree {
+            for j in 0..degree {
+                let product = &a_big[i] * &b_big[j];
+                if i + j >= degree {
+                    expected_mul[i + j - degree] -= product;
+                } else {
+                    expected_mul[i + j] += product;
+                }
+            }
+        }
+        let expected_mul: Vec<BigInt> = expected_mul.iter().map(|x| x.mod_floor(&q)).collect();
+        assert_eq!((a.clone() * b.clone()).to_bigint(), expected_mul);
+
+        let expected_add: Vec<BigInt> = (0..degree)
+            .map(|i| (&a_big[i] + &b_big[i]).mod_floor(&q))
+            .collect();
+        assert_eq!((a.clone() + b.clone()).to_bigint(), expected_add);
+
+        let expected_sub: Vec<BigInt> = (0..degree)
+            .map(|i| (&a_big[i] - &b_big[i]).mod_floor(&q))
+            .collect();
+        assert_eq!((a.clone() - b.clone()).to_bigint(), expected_sub);
+        assert_eq!(
+            (-a.clone() + a.clone()).to_bigint(),
+            vec![BigInt::zero(); degree]
+        );
+    }
+
+    #[test]
+    fn scale_round_mod_t_test() {
+        let degree = 64;
+        let basis = RnsBasis::generate(5, 60, degree);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(26);
+
+        for t in [2, 17, 65537, 1 << 40].iter() {
+            let x = RnsPoly::uniform(&basis, &mut rng);
+            let t_big = BigInt::from(*t);
+            let expected =
+                BigPoly::new(x.to_bigint()).scale_round(&t_big, basis.modulus()) % (&t_big, degree);
+            assert_eq!(x.scale_round_mod_t(*t), expected.to_poly());
+        }
+    }
+
+    #[test]
+    fn decompose_test() {
+        let basis = RnsBasis::generate(3, 30, 4);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(25);
+        let a = RnsPoly::uniform(&basis, &mut rng);
+
+        let recomposed = a
+            .decompose()
+            .iter()
+            .zip(RnsPoly::gadget(&basis).iter())
+            .fold(RnsPoly::zero(&basis), |acc, (a_i, g_i)| {
+                acc + a_i.mul_bigint(g_i)
+            });
+        assert_eq!(recomposed, a);
+    }
+}
@@ -0,0 +1,181 @@
+use super::random_source::SecretDistribution;
+use std::f64::consts::{E, PI};
+
+/// An estimate of the cost of the primal uSVP attack on a Ring-LWE instance.
+///
+/// * `block_size` = the smallest BKZ block size beta for which the attack succeeds
+/// * `samples` = the number of LWE samples m used by the attack
+/// * `core_svp_bits` = the core-SVP hardness `0.292 * beta`, i.e. the cost of a single call to a
+///   sieving SVP oracle in dimension beta
+/// * `bits` = the cost of the whole reduction, `0.292 * beta + 16.4 + log_2(8 * d)` for a lattice
+///   of dimension d, which is the cost model used to compute the tables of the security standard
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SecurityEstimate {
+    pub block_size: usize,
+    pub samples: usize,
+    pub core_svp_bits: f64,
+    pub bits: f64,
+}
+
+/// What to do when keys are generated for parameters that are estimated to be insecure.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SecurityPolicy {
+    /// Don't estimate the security of the parameters
+    Ignore,
+    /// Generate keys anyway if the estimated security is below `min_bits`. Key generation
+    /// doesn't report it: the warning is only returned by
+    /// `Parameters::try_enforce_security_policy`, which can be called before generating keys
+    Warn { min_bits: u32 },
+    /// Refuse to generate keys if the estimated security is below `min_bits`: the key generation
+    /// methods panic, and their `try_` variants return `Error::InvalidParameters`
+    Reject { min_bits: u32 },
+}
+
+/// Estimate the security of the Ring-LWE instance underlying B/FV, against the primal uSVP attack.
+///
+/// The attack embeds m samples `(a, a * s + e)` into a lattice of dimension `d = m + N + 1` that
+/// contains the unusually short vector `(e, s, 1)`, and runs BKZ with block size beta to find it.
+/// Following [ADPS16] and [AGVW17], the attack succeeds when
+/// $ sigma * sqrt(beta) <= delta^(2 * beta - d) * Vol(L)^(1 / d) $,
+/// where delta is the root Hermite factor of BKZ-beta, and the secret part of the lattice
+/// is rescaled so that s has the same standard deviation as e. The estimate is the smallest
+/// such beta over all choices of m up to 2N.
+///
+/// The estimate is within 4 bits of the 128-bit tables of the HomomorphicEncryption.org standard
+/// (131.6 bits for N = 1024 and log q = 27), within 6 bits of the 192-bit tables and within 10
+/// bits of the 256-bit tables, and tends to overestimate the security. It only considers the
+/// primal attack, so it should be read as an upper bound on the security.
+///
+/// * `degree`: the degree N of the polynomial modulus
+/// * `modulus_bits`: log_2(q), for the ciphertext modulus q
+/// * `std_dev`: the standard deviation of the error
+/// * `secret`: the distribution of the secret key
+///
+/// ```rust
+/// use bfv12::{estimate_security, SecretDistribution};
+///
+/// // The 128-bit parameters of the security standard for N = 4096
+/// let estimate = estimate_security(4096, 109.0, 3.2, SecretDistribution::Ternary);
+/// assert!((estimate.bits - 128.0).abs() < 2.0);
+///
+/// // Toy parameters provide no security
+/// let estimate = estimate_security(4, 16.0, 3.2, SecretDistribution::Binary);
+/// assert!(estimate.bits < 64.0);
+/// ```
+///
+/// [ADPS16]: https://eprint.iacr.org/2015/1092.pdf
+/// [AGVW17]: https://eprint.iacr.org/2017/815.pdf
+pub fn estimate_security(
+    degree: usize,
+    modulus_bits: f64,
+    std_dev: f64,
+    secret: SecretDistribution,
+) -> SecurityEstimate {
+    let n = degree;
+    // Rescaling the secret by nu = sigma / sigma_s balances it with the error.
+    let log_nu = (std_dev / secret.std_dev(n)).max(1.0).log2();
+    let max_samples = 2 * n;
+    let step = (max_samples / 256).max(1);
+
+    // The success condition holds for all larger block sizes, so take the first that succeeds.
+    // Block sizes below 40 are not meaningful for the root Hermite factor estimate.
+    let (block_size, samples) = (40..)
+        .find_map(|beta| {
+            let beta_f = beta as f64;
+            let log_delta = root_hermite_factor(beta_f).log2();
+            let lhs = std_dev.log2() + 0.5 * beta_f.log2();
+            (step..=max_samples).step_by(step).find_map(|m| {
+                let d = (m + n + 1) as f64;
+                // With beta >= d, BKZ solves SVP in the whole lattice.
+                let rhs = (2.0 * beta_f - d) * log_delta
+                    + (m as f64 * modulus_bits + n as f64 * log_nu) / d;
+                if beta_f >= d || rhs >= lhs {
+                    Some((beta, m))
+                } else {
+                    None
+                }
+            })
+        })
+        .unwrap();
+
+    let core_svp_bits = 0.292 * block_size as f64;
+    let dimension = (samples + n + 1) as f64;
+    SecurityEstimate {
+        block_size,
+        samples,
+        core_svp_bits,
+        bits: core_svp_bits + 16.4 + (8.0 * dimension).log2(),
+    }
+}
+
+// The root Hermite factor achieved by BKZ with block size beta:
+// $ delta = ((pi * beta)^(1 / beta) * beta / (2 * pi * e))^(1 / (2 * (beta - 1))) $
+fn root_hermite_factor(beta: f64) -> f64 {
+    ((PI * beta).powf(1.0 / beta) * beta / (2.0 * PI * E)).powf(1.0 / (2.0 * (beta - 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_tables_test() {
+        // (degree, log_2(q)) at the 128, 192 and 256-bit security levels of the standard, with
+        // the largest error of the estimate at each level
+        let tables = [
+            (
+                128.0,
+                4.0,
+                [(1024, 27), (2048, 54), (4096, 109), (8192, 218)],
+            ),
+            (
+                192.0,
+                6.0,
+                [(1024, 19), (2048, 37), (4096, 75), (8192, 152)],
+            ),
+            (
+                256.0,
+                10.0,
+                [(1024, 14), (2048, 29), (4096, 58), (8192, 118)],
+            ),
+        ];
+        for (level, error, rows) in tables.iter() {
+            for (degree, modulus_bits) in rows.iter() {
+                let estimate = estimate_security(
+                    *degree,
+                    *modulus_bits as f64,
+                    3.2,
+                    SecretDistribution::Ternary,
+                );
+                assert!(
+                    (estimate.bits - level).abs() < *error,
+                    "N = {}, log q = {}: estimated {} bits, expected {}",
+                    degree,
+                    modulus_bits,
+                    estimate.bits,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn monotonicity_test() {
+        let estimate =
+            |modulus_bits: f64, secret| estimate_security(2048, modulus_bits, 3.2, secret);
+        let ternary = SecretDistribution::Ternary;
+
+        // A larger modulus is less secure
+        assert!(estimate(40.0, ternary).bits > estimate(54.0, ternary).bits);
+        assert!(estimate(54.0, ternary).bits > estimate(60.0, ternary).bits);
+        // A smaller secret is less secure
+        assert!(estimate(54.0, ternary).bits >= estimate(54.0, SecretDistribution::Binary).bits);
+        let sparse = SecretDistribution::SparseTernary { hamming_weight: 64 };
+        assert!(estimate(54.0, SecretDistribution::Binary).bits > estimate(54.0, sparse).bits);
+        // A larger degree is more secure
+        assert!(
+            estimate_security(4096, 54.0, 3.2, ternary).block_size
+                > estimate(54.0, ternary).block_size
+        );
+    }
+}
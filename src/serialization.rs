@@ -0,0 +1,1259 @@
+use super::big_poly::BigPoly;
+use super::ciphertext::{Ciphertext, CompressedCiphertext, RnsCiphertext};
+use super::keys::{
+    PublicKey, RelinearizationKey1, RelinearizationKey2, RnsPublicKey, RnsRelinearizationKey,
+    SecretKey,
+};
+use super::ntt;
+use super::params::{Parameters, ParametersError, RnsParameters};
+use super::plaintext::Plaintext;
+use super::poly::Poly;
+use super::random_source::{ErrorDistribution, SecretDistribution, Seed, Seeded};
+use super::rns::{RnsBasis, RnsPoly};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+// The binary format of an object is:
+//
+// * the magic number "BFV\0" and the format version, as a byte
+// * the type of the object, as a byte
+// * for keys other than the secret key and for ciphertexts, the parameters: the degree N, q and
+//   t, the error distribution, the secret distribution and the relinearization base T. Integers
+//   are 8 bytes, little-endian, and the distributions are a tag byte followed by their parameter,
+//   if any. The security policy is not serialized. For RNS keys and ciphertexts, q is replaced by
+//   the number of primes in the basis followed by the primes, and there is no relinearization
+//   base.
+// * for keys other than the secret key and for ciphertexts, the identifier of the secret key,
+//   as 8 bytes
+// * the body of the object. Polynomials are bit-packed, with each coefficient in [0, q) taking
+//   ceil(log_2(q)) bits, and uniformly random polynomials that were sampled from a seed are
+//   stored as a tag byte followed by just the 32-byte seed. A secret key is its degree followed
+//   by its coefficients shifted to [0, 3), a plaintext is its degree and t followed by its
+//   coefficients in [0, t), and a ciphertext starts with its noise bound, as the 8 bytes of an
+//   f64. RNS polynomials are stored as their residues, one bit-packed polynomial per prime q_i.
+//
+// With the `serde` feature, keys, plaintexts and ciphertexts implement `Serialize` and
+// `Deserialize` as this format, so deserializing validates them in the same way as `from_bytes`.
+//
+// The version changes with the layout. Version 2 added the identifier of the secret key, and
+// version 3 the noise bound of ciphertexts. Objects of earlier versions are rejected.
+const MAGIC: [u8; 4] = *b"BFV\0";
+const VERSION: u8 = 3;
+
+// Loading rejects larger degrees and RNS bases, rather than allocating for them.
+const MAX_DEGREE: usize = 1 << 17;
+const MAX_MODULI: usize = 64;
+
+const PUBLIC_KEY: u8 = 1;
+const RELINEARIZATION_KEY_1: u8 = 2;
+const RELINEARIZATION_KEY_2: u8 = 3;
+const COMPRESSED_CIPHERTEXT: u8 = 4;
+const SECRET_KEY: u8 = 5;
+const PLAINTEXT: u8 = 6;
+const CIPHERTEXT: u8 = 7;
+const RNS_PUBLIC_KEY: u8 = 8;
+const RNS_RELINEARIZATION_KEY: u8 = 9;
+const RNS_CIPHERTEXT: u8 = 10;
+
+const GAUSSIAN: u8 = 0;
+const CENTERED_BINOMIAL: u8 = 1;
+
+const BINARY: u8 = 0;
+const TERNARY: u8 = 1;
+const SPARSE_TERNARY: u8 = 2;
+
+const STORED: u8 = 0;
+const SEEDED: u8 = 1;
+
+/// The ways in which bytes can fail to load as a key, plaintext or ciphertext.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SerializationError {
+    /// The bytes don't start with the magic number.
+    InvalidMagic,
+    /// The format version is not supported.
+    UnsupportedVersion(u8),
+    /// The bytes hold a different type of object.
+    UnexpectedType(u8),
+    /// The bytes end before the object does.
+    Truncated,
+    /// There are bytes left after the object.
+    TrailingBytes,
+    /// A tag, count or coefficient is out of its range.
+    InvalidValue,
+    /// The parameters are invalid.
+    InvalidParameters(ParametersError),
+    /// The text is not a well-formed armored key of the expected type.
+    InvalidArmor,
+    /// The parameter hash in the armor header doesn't match the key's parameters.
+    ParametersMismatch,
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializationError::InvalidMagic => write!(f, "invalid magic number"),
+            SerializationError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            SerializationError::UnexpectedType(object_type) => {
+                write!(f, "unexpected object type {}", object_type)
+            }
+            SerializationError::Truncated => write!(f, "unexpected end of input"),
+            SerializationError::TrailingBytes => write!(f, "unexpected bytes after the object"),
+            SerializationError::InvalidValue => write!(f, "value out of range"),
+            SerializationError::InvalidParameters(err) => write!(f, "invalid parameters: {}", err),
+            SerializationError::InvalidArmor => write!(f, "invalid armored key"),
+            SerializationError::ParametersMismatch => {
+                write!(f, "the parameter hash doesn't match the parameters")
+            }
+        }
+    }
+}
+
+impl Error for SerializationError {}
+
+impl From<ParametersError> for SerializationError {
+    fn from(err: ParametersError) -> SerializationError {
+        SerializationError::InvalidParameters(err)
+    }
+}
+
+impl SecretKey {
+    /// Serialize the key. The parameters are not included, since the key doesn't hold them.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, SecretKey};
+    ///
+    /// let params = Parameters::new(1024, 1 << 40, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    ///
+    /// let bytes = secret_key.to_bytes();
+    /// let loaded = SecretKey::from_bytes(&bytes).unwrap();
+    /// assert_eq!(loaded.to_bytes(), bytes);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new(SECRET_KEY);
+        writer.secret_poly(&self.poly);
+        writer.bytes
+    }
+
+    /// Load a key serialized with `to_bytes`, checking that its degree is a power of two and
+    /// that its coefficients are in {-1, 0, 1}.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey, SerializationError> {
+        let mut reader = Reader::new(bytes, SECRET_KEY)?;
+        let degree = reader.degree()?;
+        if !degree.is_power_of_two() {
+            return Err(SerializationError::InvalidValue);
+        }
+        let values = reader.packed(degree, 2, 3)?;
+        reader.finish()?;
+        let poly = Poly::new(values.into_iter().map(|c| c as i64 - 1).collect());
+        Ok(SecretKey::from_poly(poly))
+    }
+}
+
+impl PublicKey {
+    /// Serialize the key. If it was generated with `SecretKey::public_key_gen_seeded`,
+    /// `p_1` is stored as its seed.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Parameters, PublicKey, SecretKey};
+    ///
+    /// let params = Parameters::new(1024, 1 << 40, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen_seeded(&params, &mut rng);
+    ///
+    /// let bytes = public_key.to_bytes();
+    /// let loaded = PublicKey::from_bytes(&bytes).unwrap();
+    /// assert_eq!(loaded.params(), &params);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (q, degree) = (self.params.q(), self.params.degree());
+        let mut writer = Writer::with_params(PUBLIC_KEY, &self.params);
+        writer.u64(self.id);
+        writer.poly(&self.p_0, q);
+        writer.seeded_poly(&self.p_1, q, degree);
+        writer.bytes
+    }
+
+    /// Load a key serialized with `to_bytes`, validating its parameters and coefficients.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, SerializationError> {
+        let (mut reader, params) = Reader::with_params(bytes, PUBLIC_KEY)?;
+        let (q, degree) = (params.q(), params.degree());
+        let id = reader.u64()?;
+        let p_0 = reader.poly(q, degree)?;
+        let p_1 = reader.seeded_poly(q, degree)?;
+        reader.finish()?;
+        Ok(PublicKey {
+            p_0,
+            p_1,
+            params,
+            id,
+        })
+    }
+}
+
+impl RelinearizationKey1 {
+    /// Serialize the key. If it was generated with `SecretKey::relin_key_gen_1_seeded`,
+    /// the a_i are stored as their seeds.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (q, degree) = (self.params.q(), self.params.degree());
+        let mut writer = Writer::with_params(RELINEARIZATION_KEY_1, &self.params);
+        writer.u64(self.id);
+        for (rlk_i, a_i) in self.val.iter() {
+            writer.poly(rlk_i, q);
+            writer.seeded_poly(a_i, q, degree);
+        }
+        writer.bytes
+    }
+
+    /// Load a key serialized with `to_bytes`, validating its parameters and coefficients.
+    /// The number of levels l is determined by the parameters.
+    pub fn from_bytes(bytes: &[u8]) -> Result<RelinearizationKey1, SerializationError> {
+        let (mut reader, params) = Reader::with_params(bytes, RELINEARIZATION_KEY_1)?;
+        let (q, degree, l) = (params.q(), params.degree(), params.rlk_levels());
+        let id = reader.u64()?;
+        let val = (0..l)
+            .map(|_| Ok((reader.poly(q, degree)?, reader.seeded_poly(q, degree)?)))
+            .collect::<Result<_, SerializationError>>()?;
+        reader.finish()?;
+        Ok(RelinearizationKey1 { val, l, params, id })
+    }
+}
+
+impl RelinearizationKey2 {
+    /// Serialize the key. If it was generated with `SecretKey::relin_key_gen_2_seeded`,
+    /// `rlk_1` is stored as its seed.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let p_q = BigInt::from(self.p) * self.params.q();
+        let mut writer = Writer::with_params(RELINEARIZATION_KEY_2, &self.params);
+        writer.u64(self.id);
+        writer.u64(self.p as u64);
+        writer.big_poly(&self.rlk_0, &p_q);
+        writer.seeded_big_poly(&self.rlk_1, &p_q, self.params.degree());
+        writer.bytes
+    }
+
+    /// Load a key serialized with `to_bytes`, validating its parameters and coefficients.
+    pub fn from_bytes(bytes: &[u8]) -> Result<RelinearizationKey2, SerializationError> {
+        let (mut reader, params) = Reader::with_params(bytes, RELINEARIZATION_KEY_2)?;
+        let degree = params.degree();
+        let id = reader.u64()?;
+        let p = reader.u64()? as i64;
+        if p < 1 {
+            return Err(SerializationError::InvalidValue);
+        }
+        let p_q = BigInt::from(p) * params.q();
+        let rlk_0 = reader.big_poly(&p_q, degree)?;
+        let rlk_1 = reader.seeded_big_poly(&p_q, degree)?;
+        reader.finish()?;
+        Ok(RelinearizationKey2 {
+            rlk_0,
+            rlk_1,
+            p,
+            params,
+            id,
+        })
+    }
+}
+
+impl CompressedCiphertext {
+    /// Serialize the ciphertext, with `c_1` stored as its seed.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (q, degree) = (self.params.q(), self.params.degree());
+        let mut writer = Writer::with_params(COMPRESSED_CIPHERTEXT, &self.params);
+        writer.u64(self.key_id);
+        writer.poly(&self.c_0, q);
+        writer.seeded_poly(&self.c_1, q, degree);
+        writer.bytes
+    }
+
+    /// Load a ciphertext serialized with `to_bytes`, validating its parameters and coefficients.
+    pub fn from_bytes(bytes: &[u8]) -> Result<CompressedCiphertext, SerializationError> {
+        let (mut reader, params) = Reader::with_params(bytes, COMPRESSED_CIPHERTEXT)?;
+        let (q, degree) = (params.q(), params.degree());
+        let key_id = reader.u64()?;
+        let c_0 = reader.poly(q, degree)?;
+        let c_1 = reader.seeded_poly(q, degree)?;
+        reader.finish()?;
+        Ok(CompressedCiphertext {
+            c_0,
+            c_1,
+            params,
+            key_id,
+        })
+    }
+}
+
+impl Plaintext {
+    /// Serialize the plaintext, with its coefficients reduced to [0, t).
+    ///
+    /// ```rust
+    /// use bfv12::Plaintext;
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], 4);
+    ///
+    /// let bytes = pt.to_bytes();
+    /// assert_eq!(Plaintext::from_bytes(&bytes).unwrap(), pt);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (t, degree) = (self.t(), self.poly().degree());
+        let mut writer = Writer::new(PLAINTEXT);
+        writer.u64(degree as u64);
+        writer.u64(t as u64);
+        writer.poly(&(self.poly() % (t, degree)), t);
+        writer.bytes
+    }
+
+    /// Load a plaintext serialized with `to_bytes`, checking that t > 1 and that the
+    /// coefficients are in [0, t).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Plaintext, SerializationError> {
+        let mut reader = Reader::new(bytes, PLAINTEXT)?;
+        let degree = reader.degree()?;
+        let t = reader.u64()? as i64;
+        if t < 2 {
+            return Err(SerializationError::InvalidValue);
+        }
+        let poly = reader.poly(t, degree)?;
+        reader.finish()?;
+        Ok(Plaintext::new_from_poly(poly, t))
+    }
+}
+
+impl Ciphertext {
+    /// Serialize the ciphertext.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{Ciphertext, Parameters, Plaintext, SecretKey};
+    /// let params = Parameters::new(1024, 1 << 40, 16, 3.2).unwrap();
+    /// let pt = Plaintext::rand(&params, &mut rng);
+    ///
+    /// let secret_key = SecretKey::generate(&params, &mut rng);
+    /// let public_key = secret_key.public_key_gen(&params, &mut rng);
+    /// let ct = pt.encrypt(&public_key, &mut rng);
+    ///
+    /// let bytes = ct.to_bytes();
+    /// let loaded = Ciphertext::from_bytes(&bytes).unwrap();
+    /// assert_eq!(loaded.decrypt(&secret_key), pt);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let q = self.params.q();
+        let mut writer = Writer::with_params(CIPHERTEXT, &self.params);
+        writer.u64(self.key_id);
+        writer.u64(self.noise_bound.to_bits());
+        writer.poly(&self.c_0, q);
+        writer.poly(&self.c_1, q);
+        writer.bytes
+    }
+
+    /// Load a ciphertext serialized with `to_bytes`, validating its parameters, noise bound and
+    /// coefficients.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Ciphertext, SerializationError> {
+        let (mut reader, params) = Reader::with_params(bytes, CIPHERTEXT)?;
+        let (q, degree) = (params.q(), params.degree());
+        let key_id = reader.u64()?;
+        let noise_bound = f64::from_bits(reader.u64()?);
+        if !(noise_bound.is_finite() && noise_bound >= 0.0) {
+            return Err(SerializationError::InvalidValue);
+        }
+        let c_0 = reader.poly(q, degree)?;
+        let c_1 = reader.poly(q, degree)?;
+        reader.finish()?;
+        Ok(Ciphertext {
+            c_0,
+            c_1,
+            params,
+            key_id,
+            noise_bound,
+        })
+    }
+}
+
+impl RnsPublicKey {
+    /// Serialize the key, with its polynomials stored as their residues.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use bfv12::{RnsBasis, RnsParameters, RnsPublicKey, SecretKey};
+    ///
+    /// let basis = RnsBasis::generate(2, 50, 1024);
+    /// let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+    /// let secret_key = SecretKey::generate_rns(&params, &mut rng);
+    /// let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+    ///
+    /// let bytes = public_key.to_bytes();
+    /// let loaded = RnsPublicKey::from_bytes(&bytes).unwrap();
+    /// assert_eq!(loaded.params(), &params);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::with_rns_params(RNS_PUBLIC_KEY, &self.params);
+        writer.u64(self.id);
+        writer.rns_poly(&self.p_0);
+        writer.rns_poly(&self.p_1);
+        writer.bytes
+    }
+
+    /// Load a key serialized with `to_bytes`, validating its parameters and residues.
+    pub fn from_bytes(bytes: &[u8]) -> Result<RnsPublicKey, SerializationError> {
+        let (mut reader, params) = Reader::with_rns_params(bytes, RNS_PUBLIC_KEY)?;
+        let id = reader.u64()?;
+        let p_0 = reader.rns_poly(params.basis())?;
+        let p_1 = reader.rns_poly(params.basis())?;
+        reader.finish()?;
+        Ok(RnsPublicKey {
+            p_0,
+            p_1,
+            params,
+            id,
+        })
+    }
+}
+
+impl RnsRelinearizationKey {
+    /// Serialize the key, with its polynomials stored as their residues.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::with_rns_params(RNS_RELINEARIZATION_KEY, &self.params);
+        writer.u64(self.id);
+        for (rlk_i, a_i) in self.val.iter() {
+            writer.rns_poly(rlk_i);
+            writer.rns_poly(a_i);
+        }
+        writer.bytes
+    }
+
+    /// Load a key serialized with `to_bytes`, validating its parameters and residues.
+    /// There is one level per prime in the RNS basis.
+    pub fn from_bytes(bytes: &[u8]) -> Result<RnsRelinearizationKey, SerializationError> {
+        let (mut reader, params) = Reader::with_rns_params(bytes, RNS_RELINEARIZATION_KEY)?;
+        let basis = params.basis().clone();
+        let id = reader.u64()?;
+        let val = (0..basis.moduli().len())
+            .map(|_| Ok((reader.rns_poly(&basis)?, reader.rns_poly(&basis)?)))
+            .collect::<Result<_, SerializationError>>()?;
+        reader.finish()?;
+        Ok(RnsRelinearizationKey { val, params, id })
+    }
+}
+
+impl RnsCiphertext {
+    /// Serialize the ciphertext, with its polynomials stored as their residues.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::with_rns_params(RNS_CIPHERTEXT, &self.params);
+        writer.u64(self.key_id);
+        writer.rns_poly(&self.c_0);
+        writer.rns_poly(&self.c_1);
+        writer.bytes
+    }
+
+    /// Load a ciphertext serialized with `to_bytes`, validating its parameters and residues.
+    pub fn from_bytes(bytes: &[u8]) -> Result<RnsCiphertext, SerializationError> {
+        let (mut reader, params) = Reader::with_rns_params(bytes, RNS_CIPHERTEXT)?;
+        let key_id = reader.u64()?;
+        let c_0 = reader.rns_poly(params.basis())?;
+        let c_1 = reader.rns_poly(params.basis())?;
+        reader.finish()?;
+        Ok(RnsCiphertext {
+            c_0,
+            c_1,
+            params,
+            key_id,
+        })
+    }
+}
+
+/// A 64-bit FNV-1a hash of the serialized parameters, which identifies them.
+pub(crate) fn params_hash(params: &Parameters) -> u64 {
+    let mut writer = Writer { bytes: Vec::new() };
+    writer.params(params);
+    fnv_1a(&writer.bytes)
+}
+
+/// A 64-bit FNV-1a hash of the serialized RNS parameters, which identifies them.
+pub(crate) fn rns_params_hash(params: &RnsParameters) -> u64 {
+    let mut writer = Writer { bytes: Vec::new() };
+    writer.rns_params(params);
+    fnv_1a(&writer.bytes)
+}
+
+/// A 64-bit FNV-1a hash of the serialized secret key polynomial, which identifies the key.
+pub(crate) fn secret_key_hash(poly: &Poly) -> u64 {
+    let mut writer = Writer { bytes: Vec::new() };
+    writer.secret_poly(poly);
+    fnv_1a(&writer.bytes)
+}
+
+fn fnv_1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// The number of bits needed for values in [0, modulus), for modulus >= 2.
+fn bit_width(modulus: u128) -> u32 {
+    128 - (modulus - 1).leading_zeros()
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn new(object_type: u8) -> Writer {
+        let mut writer = Writer { bytes: Vec::new() };
+        writer.bytes.extend_from_slice(&MAGIC);
+        writer.u8(VERSION);
+        writer.u8(object_type);
+        writer
+    }
+
+    fn with_params(object_type: u8, params: &Parameters) -> Writer {
+        let mut writer = Writer::new(object_type);
+        writer.params(params);
+        writer
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn seed(&mut self, seed: &Seed) {
+        self.u8(SEEDED);
+        self.bytes.extend_from_slice(seed);
+    }
+
+    fn params(&mut self, params: &Parameters) {
+        self.u64(params.degree() as u64);
+        self.u64(params.q() as u64);
+        self.u64(params.t() as u64);
+        self.distributions(params.error_distribution(), params.secret_distribution());
+        self.u64(params.rlk_base() as u64);
+    }
+
+    fn with_rns_params(object_type: u8, params: &RnsParameters) -> Writer {
+        let mut writer = Writer::new(object_type);
+        writer.rns_params(params);
+        writer
+    }
+
+    fn rns_params(&mut self, params: &RnsParameters) {
+        let moduli = params.basis().moduli();
+        self.u64(params.degree() as u64);
+        self.u64(moduli.len() as u64);
+        for q_i in moduli.iter() {
+            self.u64(*q_i as u64);
+        }
+        self.u64(params.t() as u64);
+        self.distributions(params.error_distribution(), params.secret_distribution());
+    }
+
+    fn distributions(
+        &mut self,
+        error_distribution: ErrorDistribution,
+        secret_distribution: SecretDistribution,
+    ) {
+        match error_distribution {
+            ErrorDistribution::Gaussian { std_dev } => {
+                self.u8(GAUSSIAN);
+                self.u64(std_dev.to_bits());
+            }
+            ErrorDistribution::CenteredBinomial { eta } => {
+                self.u8(CENTERED_BINOMIAL);
+                self.u64(eta as u64);
+            }
+        }
+        match secret_distribution {
+            SecretDistribution::Binary => self.u8(BINARY),
+            SecretDistribution::Ternary => self.u8(TERNARY),
+            SecretDistribution::SparseTernary { hamming_weight } => {
+                self.u8(SPARSE_TERNARY);
+                self.u64(hamming_weight as u64);
+            }
+        }
+    }
+
+    // Pack the values, least significant bit first, in `width` bits each.
+    fn packed<I: Iterator<Item = u128>>(&mut self, values: I, width: u32) {
+        // At most 7 bits are left over after each write of at most 64 bits.
+        let (mut buffer, mut buffered) = (0u128, 0);
+        for value in values {
+            let mut written = 0;
+            while written < width {
+                let bits = (width - written).min(64);
+                let chunk = (value >> written) & ((1u128 << bits) - 1);
+                buffer |= chunk << buffered;
+                buffered += bits;
+                written += bits;
+                while buffered >= 8 {
+                    self.bytes.push(buffer as u8);
+                    buffer >>= 8;
+                    buffered -= 8;
+                }
+            }
+        }
+        if buffered > 0 {
+            self.bytes.push(buffer as u8);
+        }
+    }
+
+    // The degree, followed by the coefficients in {-1, 0, 1} shifted to [0, 3).
+    fn secret_poly(&mut self, poly: &Poly) {
+        self.u64(poly.degree() as u64);
+        self.packed(poly.val().into_iter().map(|c| (c + 1) as u128), 2);
+    }
+
+    fn poly(&mut self, poly: &Poly, q: i64) {
+        let values = poly.val().into_iter().map(|c| c as u128);
+        self.packed(values, bit_width(q as u128));
+    }
+
+    fn rns_poly(&mut self, poly: &RnsPoly) {
+        let moduli = poly.basis().moduli();
+        for (q_i, residues) in moduli.iter().zip(poly.residues().iter()) {
+            let values = residues.iter().map(|c| *c as u128);
+            self.packed(values, bit_width(*q_i as u128));
+        }
+    }
+
+    fn big_poly(&mut self, poly: &BigPoly, modulus: &BigInt) {
+        let values = poly.val().into_iter().map(|c| c.to_u128().unwrap());
+        self.packed(values, bit_width(modulus.to_u128().unwrap()));
+    }
+
+    fn seeded_poly(&mut self, poly: &Seeded<Poly>, q: i64, degree: usize) {
+        match poly.seed() {
+            Some(seed) => self.seed(seed),
+            None => {
+                self.u8(STORED);
+                self.poly(poly.get_uniform(q, degree), q);
+            }
+        }
+    }
+
+    fn seeded_big_poly(&mut self, poly: &Seeded<BigPoly>, modulus: &BigInt, degree: usize) {
+        match poly.seed() {
+            Some(seed) => self.seed(seed),
+            None => {
+                self.u8(STORED);
+                self.big_poly(poly.get_uniform_bigint(modulus, degree), modulus);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    // Check the header.
+    fn new(bytes: &'a [u8], object_type: u8) -> Result<Reader<'a>, SerializationError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(SerializationError::InvalidMagic);
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(SerializationError::UnsupportedVersion(version));
+        }
+        let found = reader.u8()?;
+        if found != object_type {
+            return Err(SerializationError::UnexpectedType(found));
+        }
+        Ok(reader)
+    }
+
+    // Check the header, and read the parameters.
+    fn with_params(
+        bytes: &'a [u8],
+        object_type: u8,
+    ) -> Result<(Reader<'a>, Parameters), SerializationError> {
+        let mut reader = Reader::new(bytes, object_type)?;
+        let params = reader.params()?;
+        Ok((reader, params))
+    }
+
+    // Check the header, and read the RNS parameters.
+    fn with_rns_params(
+        bytes: &'a [u8],
+        object_type: u8,
+    ) -> Result<(Reader<'a>, RnsParameters), SerializationError> {
+        let mut reader = Reader::new(bytes, object_type)?;
+        let params = reader.rns_params()?;
+        Ok((reader, params))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SerializationError> {
+        if self.bytes.len() < len {
+            return Err(SerializationError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn finish(&self) -> Result<(), SerializationError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(SerializationError::TrailingBytes)
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8, SerializationError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64, SerializationError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn seed(&mut self) -> Result<Seed, SerializationError> {
+        let mut seed = [0; 32];
+        seed.copy_from_slice(self.take(32)?);
+        Ok(seed)
+    }
+
+    fn degree(&mut self) -> Result<usize, SerializationError> {
+        let degree = self.u64()?;
+        if degree > MAX_DEGREE as u64 {
+            return Err(SerializationError::InvalidValue);
+        }
+        Ok(degree as usize)
+    }
+
+    fn params(&mut self) -> Result<Parameters, SerializationError> {
+        let degree = self.degree()?;
+        let (q, t) = (self.u64()? as i64, self.u64()? as i64);
+        let (error_distribution, secret_distribution) = self.distributions()?;
+        let rlk_base = self.u64()? as i64;
+
+        // The standard deviation is replaced by the error distribution right away.
+        let params = Parameters::new(degree, q, t, 1.0)?
+            .with_error_distribution(error_distribution)?
+            .with_secret_distribution(secret_distribution)?
+            .with_rlk_base(rlk_base)?;
+        Ok(params)
+    }
+
+    // The primes are checked before building the basis, which panics on invalid primes.
+    fn rns_params(&mut self) -> Result<RnsParameters, SerializationError> {
+        let degree = self.degree()?;
+        let count = self.u64()?;
+        if count == 0 || count > MAX_MODULI as u64 {
+            return Err(SerializationError::InvalidValue);
+        }
+        let mut moduli: Vec<i64> = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let q_i = self.u64()?;
+            if q_i > i64::MAX as u64
+                || moduli.contains(&(q_i as i64))
+                || !ntt::is_ntt_friendly(q_i as i64, degree)
+            {
+                return Err(SerializationError::InvalidValue);
+            }
+            moduli.push(q_i as i64);
+        }
+        let t = self.u64()? as i64;
+        let (error_distribution, secret_distribution) = self.distributions()?;
+
+        let basis = RnsBasis::new(moduli, degree);
+        let params = RnsParameters::new(&basis, t, 1.0)?
+            .with_error_distribution(error_distribution)?
+            .with_secret_distribution(secret_distribution)?;
+        Ok(params)
+    }
+
+    fn distributions(
+        &mut self,
+    ) -> Result<(ErrorDistribution, SecretDistribution), SerializationError> {
+        let error_distribution = match self.u8()? {
+            GAUSSIAN => ErrorDistribution::Gaussian {
+                std_dev: f64::from_bits(self.u64()?),
+            },
+            CENTERED_BINOMIAL => ErrorDistribution::CenteredBinomial {
+                eta: self.u64()?.min(u32::MAX as u64) as u32,
+            },
+            _ => return Err(SerializationError::InvalidValue),
+        };
+        let secret_distribution = match self.u8()? {
+            BINARY => SecretDistribution::Binary,
+            TERNARY => SecretDistribution::Ternary,
+            SPARSE_TERNARY => SecretDistribution::SparseTernary {
+                hamming_weight: self.u64()?.min(MAX_DEGREE as u64 + 1) as usize,
+            },
+            _ => return Err(SerializationError::InvalidValue),
+        };
+        Ok((error_distribution, secret_distribution))
+    }
+
+    // Unpack `count` values of `width` bits each, and check that they are below `modulus`.
+    fn packed(
+        &mut self,
+        count: usize,
+        width: u32,
+        modulus: u128,
+    ) -> Result<Vec<u128>, SerializationError> {
+        let len = (count * width as usize).div_ceil(8);
+        let mut bytes = self.take(len)?.iter();
+        let (mut buffer, mut buffered) = (0u128, 0);
+        (0..count)
+            .map(|_| {
+                let mut value = 0u128;
+                let mut read = 0;
+                while read < width {
+                    let bits = (width - read).min(64);
+                    while buffered < bits {
+                        buffer |= (*bytes.next().unwrap() as u128) << buffered;
+                        buffered += 8;
+                    }
+                    value |= (buffer & ((1u128 << bits) - 1)) << read;
+                    buffer >>= bits;
+                    buffered -= bits;
+                    read += bits;
+                }
+                if value < modulus {
+                    Ok(value)
+                } else {
+                    Err(SerializationError::InvalidValue)
+                }
+            })
+            .collect()
+    }
+
+    fn poly(&mut self, q: i64, degree: usize) -> Result<Poly, SerializationError> {
+        let values = self.packed(degree, bit_width(q as u128), q as u128)?;
+        Ok(Poly::new(values.into_iter().map(|c| c as i64).collect()))
+    }
+
+    fn rns_poly(&mut self, basis: &Arc<RnsBasis>) -> Result<RnsPoly, SerializationError> {
+        let residues = basis
+            .moduli()
+            .iter()
+            .map(|q_i| {
+                let values = self.packed(basis.degree(), bit_width(*q_i as u128), *q_i as u128)?;
+                Ok(values.into_iter().map(|c| c as i64).collect())
+            })
+            .collect::<Result<_, SerializationError>>()?;
+        Ok(RnsPoly::from_residues(residues, basis))
+    }
+
+    fn big_poly(&mut self, modulus: &BigInt, degree: usize) -> Result<BigPoly, SerializationError> {
+        let modulus = modulus.to_u128().unwrap();
+        let values = self.packed(degree, bit_width(modulus), modulus)?;
+        Ok(BigPoly::new(values.into_iter().map(BigInt::from).collect()))
+    }
+
+    fn seeded_poly(&mut self, q: i64, degree: usize) -> Result<Seeded<Poly>, SerializationError> {
+        match self.u8()? {
+            STORED => Ok(Seeded::from_value(self.poly(q, degree)?)),
+            SEEDED => Ok(Seeded::from_seed(self.seed()?)),
+            _ => Err(SerializationError::InvalidValue),
+        }
+    }
+
+    fn seeded_big_poly(
+        &mut self,
+        modulus: &BigInt,
+        degree: usize,
+    ) -> Result<Seeded<BigPoly>, SerializationError> {
+        match self.u8()? {
+            STORED => Ok(Seeded::from_value(self.big_poly(modulus, degree)?)),
+            SEEDED => Ok(Seeded::from_seed(self.seed()?)),
+            _ => Err(SerializationError::InvalidValue),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct BytesVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a byte array")
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: serde::de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(bytes)
+    }
+
+    // Formats without a byte array type, such as JSON, store bytes as a sequence.
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(1 << 20));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+// Implement `Serialize` and `Deserialize` through `to_bytes` and `from_bytes`.
+macro_rules! impl_serde {
+    ($($name:ident),*) => {
+        $(
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for $name {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_bytes(&self.to_bytes())
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> serde::Deserialize<'de> for $name {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                    let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+                    $name::from_bytes(&bytes).map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+impl_serde!(
+    SecretKey,
+    PublicKey,
+    RelinearizationKey1,
+    RelinearizationKey2,
+    Plaintext,
+    Ciphertext,
+    CompressedCiphertext,
+    RnsPublicKey,
+    RnsRelinearizationKey,
+    RnsCiphertext
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::SecretKey;
+    use rand::SeedableRng;
+
+    #[test]
+    fn packing_test() {
+        let params = Parameters::new(4, 65536, 12, 3.2).unwrap();
+        for width in [1, 7, 8, 13, 61, 64, 65, 100, 125].iter() {
+            let modulus = 1u128 << width;
+            let values = vec![0, modulus - 1, modulus / 3, 1];
+            let mut writer = Writer::with_params(PUBLIC_KEY, &params);
+            let header_len = writer.bytes.len();
+            writer.packed(values.iter().cloned(), *width);
+            assert_eq!(
+                writer.bytes.len() - header_len,
+                (4 * *width as usize).div_ceil(8)
+            );
+
+            let (mut reader, _) = Reader::with_params(&writer.bytes, PUBLIC_KEY).unwrap();
+            assert_eq!(reader.packed(4, *width, modulus).unwrap(), values);
+            reader.finish().unwrap();
+        }
+    }
+
+    #[test]
+    fn key_round_trip_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(27);
+        let params = Parameters::new(16, 65537, 12, 3.2)
+            .and_then(|params| params.with_secret_distribution(SecretDistribution::Ternary))
+            .unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let p = params.q() * params.q() * params.q();
+
+        for seeded in [false, true].iter() {
+            let public_key = if *seeded {
+                secret_key.public_key_gen_seeded(&params, &mut rng)
+            } else {
+                secret_key.public_key_gen(&params, &mut rng)
+            };
+            let loaded = PublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+            assert_eq!(loaded.params, params);
+            assert_eq!(loaded.p_0, public_key.p_0);
+            assert_eq!(loaded.p_1(), public_key.p_1());
+            assert_eq!(loaded.p_1.seed(), public_key.p_1.seed());
+
+            let rlk_1 = if *seeded {
+                secret_key.relin_key_gen_1_seeded(&params, &mut rng)
+            } else {
+                secret_key.relin_key_gen_1(&params, &mut rng)
+            };
+            let loaded = RelinearizationKey1::from_bytes(&rlk_1.to_bytes()).unwrap();
+            assert_eq!(loaded.l, rlk_1.l);
+            assert!(loaded.levels().eq(rlk_1.levels()));
+
+            let rlk_2 = if *seeded {
+                secret_key.relin_key_gen_2_seeded(&params, p, &mut rng)
+            } else {
+                secret_key.relin_key_gen_2(&params, p, &mut rng)
+            };
+            let loaded = RelinearizationKey2::from_bytes(&rlk_2.to_bytes()).unwrap();
+            assert_eq!(loaded.p, p);
+            assert_eq!(loaded.rlk_0, rlk_2.rlk_0);
+            assert_eq!(loaded.rlk_1(), rlk_2.rlk_1());
+        }
+    }
+
+    #[test]
+    fn value_round_trip_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(30);
+        let params = Parameters::new(16, 65537, 12, 3.2)
+            .and_then(|params| params.with_secret_distribution(SecretDistribution::Ternary))
+            .unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+
+        let bytes = secret_key.to_bytes();
+        assert_eq!(bytes.len(), 6 + 8 + 4);
+        let loaded = SecretKey::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.poly, secret_key.poly);
+
+        // Coefficients are reduced to [0, t) when serialized
+        let pt = Plaintext::new(vec![-1, 13, 2, 3], 12);
+        let loaded = Plaintext::from_bytes(&pt.to_bytes()).unwrap();
+        assert_eq!(loaded, Plaintext::new(vec![11, 1, 2, 3], 12));
+
+        let pt = Plaintext::rand(&params, &mut rng);
+        let ct = pt.encrypt(&public_key, &mut rng);
+        let loaded = Ciphertext::from_bytes(&ct.to_bytes()).unwrap();
+        assert_eq!(loaded.params, params);
+        assert_eq!(loaded.noise_bound, ct.noise_bound);
+        assert_eq!((loaded.c_0, loaded.c_1), (ct.c_0, ct.c_1));
+
+        // A secret key coefficient of 2, stored as 3
+        let mut invalid = bytes.clone();
+        invalid[14] |= 0x03;
+        assert_eq!(
+            SecretKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+        // A secret key degree that is not a power of two
+        invalid = bytes;
+        invalid[6] = 15;
+        assert_eq!(
+            SecretKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+
+        // A plaintext modulus of 1
+        let mut invalid = pt.to_bytes();
+        invalid[14..22].copy_from_slice(&1u64.to_le_bytes());
+        assert_eq!(
+            Plaintext::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+    }
+
+    #[test]
+    fn rns_round_trip_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(43);
+        let basis = RnsBasis::generate(2, 30, 16);
+        let params = RnsParameters::new(&basis, 12, 3.2)
+            .and_then(|params| params.with_secret_distribution(SecretDistribution::Ternary))
+            .unwrap();
+        let secret_key = SecretKey::generate_rns(&params, &mut rng);
+        let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+        let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+
+        let loaded_public_key = RnsPublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        assert_eq!(loaded_public_key.params, params);
+        assert_eq!(loaded_public_key.params.id(), params.id());
+        assert_eq!(loaded_public_key.id, public_key.id);
+        assert_eq!(loaded_public_key.p_0, public_key.p_0);
+        assert_eq!(loaded_public_key.p_1, public_key.p_1);
+
+        let loaded_relin_key = RnsRelinearizationKey::from_bytes(&relin_key.to_bytes()).unwrap();
+        assert_eq!(loaded_relin_key.val, relin_key.val);
+
+        let pt = Plaintext::new(vec![3; 16], 12);
+        let ct = pt.encrypt_rns(&loaded_public_key, &mut rng);
+        let bytes = ct.to_bytes();
+        let loaded = RnsCiphertext::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.key_id, ct.key_id);
+        assert_eq!((&loaded.c_0, &loaded.c_1), (&ct.c_0, &ct.c_1));
+        let squared = loaded.clone() * (loaded, &loaded_relin_key);
+        assert_eq!(
+            squared.decrypt(&secret_key).poly(),
+            (pt.poly() * pt.poly()) % (12, 16)
+        );
+
+        // The primes start after the header, the degree and the number of primes
+        let mut invalid = bytes.clone();
+        invalid[22..30].copy_from_slice(&65536u64.to_le_bytes());
+        assert_eq!(
+            RnsCiphertext::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+        // A repeated prime
+        invalid = bytes.clone();
+        invalid.copy_within(22..30, 30);
+        assert_eq!(
+            RnsCiphertext::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+        // No primes
+        invalid = bytes.clone();
+        invalid[14..22].copy_from_slice(&0u64.to_le_bytes());
+        assert_eq!(
+            RnsCiphertext::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+        // A residue of c_0 that is not below q_0, which is a 30-bit prime
+        invalid = bytes;
+        let header_len = Writer::with_rns_params(RNS_CIPHERTEXT, &params).bytes.len() + 8;
+        invalid[header_len..header_len + 4].copy_from_slice(&[0xff, 0xff, 0xff, 0x3f]);
+        assert_eq!(
+            RnsCiphertext::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(32);
+        let params = Parameters::new(16, 65537, 12, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen_seeded(&params, &mut rng);
+        let pt = Plaintext::rand(&params, &mut rng);
+        let ct = pt.encrypt(&public_key, &mut rng);
+
+        let json = serde_json::to_string(&secret_key).unwrap();
+        let loaded: SecretKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.poly, secret_key.poly);
+
+        let json = serde_json::to_string(&public_key).unwrap();
+        let loaded: PublicKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            (&loaded.p_0, loaded.p_1()),
+            (&public_key.p_0, public_key.p_1())
+        );
+
+        let json = serde_json::to_string(&pt).unwrap();
+        assert_eq!(serde_json::from_str::<Plaintext>(&json).unwrap(), pt);
+
+        let json = serde_json::to_string(&ct).unwrap();
+        let loaded: Ciphertext = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.decrypt(&secret_key), pt);
+
+        let rns_params = RnsParameters::new(&RnsBasis::generate(2, 30, 16), 12, 3.2).unwrap();
+        let rns_secret_key = SecretKey::generate_rns(&rns_params, &mut rng);
+        let rns_public_key = rns_secret_key.rns_public_key_gen(&rns_params, &mut rng);
+        let rns_pt = Plaintext::new(pt.poly().val(), 12);
+        let rns_ct = rns_pt.encrypt_rns(&rns_public_key, &mut rng);
+        let json = serde_json::to_string(&rns_ct).unwrap();
+        let loaded: RnsCiphertext = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.decrypt(&rns_secret_key), rns_pt);
+
+        // Deserialization validates the bytes, here a coefficient of c_0 that is not below q
+        let mut bytes = ct.to_bytes();
+        // The header, followed by the key identifier and the noise bound
+        let header_len = Writer::with_params(CIPHERTEXT, &params).bytes.len() + 16;
+        bytes[header_len..header_len + 3].copy_from_slice(&[0xff, 0xff, 0xff]);
+        let json = serde_json::to_string(&bytes).unwrap();
+        let err = serde_json::from_str::<Ciphertext>(&json).unwrap_err();
+        assert_eq!(err.to_string(), "value out of range");
+
+        let poly = Poly::new(vec![-1, 0, 5]);
+        let json = serde_json::to_string(&poly).unwrap();
+        assert_eq!(serde_json::from_str::<Poly>(&json).unwrap(), poly);
+    }
+
+    #[test]
+    fn seeded_size_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(28);
+        let params = Parameters::new(1024, 1 << 40, 12, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+
+        // Each polynomial takes 1024 * 40 bits, and each seed 32 bytes, after the header and the
+        // key identifier
+        let header_len = Writer::with_params(PUBLIC_KEY, &params).bytes.len() + 8;
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        assert_eq!(public_key.to_bytes().len(), header_len + 2 * 5120 + 1);
+        let public_key = secret_key.public_key_gen_seeded(&params, &mut rng);
+        assert_eq!(public_key.to_bytes().len(), header_len + 5120 + 33);
+
+        let pt = crate::plaintext::Plaintext::rand(&params, &mut rng);
+        let compressed = secret_key.encrypt_compressed(&pt, &params, &mut rng);
+        assert_eq!(compressed.to_bytes().len(), header_len + 5120 + 33);
+    }
+
+    #[test]
+    fn invalid_bytes_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(29);
+        let params = Parameters::new(16, 65537, 12, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let bytes = secret_key.public_key_gen(&params, &mut rng).to_bytes();
+
+        let mut invalid = bytes.clone();
+        invalid[0] = b'X';
+        assert_eq!(
+            PublicKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidMagic
+        );
+        invalid = bytes.clone();
+        invalid[4] = VERSION + 1;
+        assert_eq!(
+            PublicKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::UnsupportedVersion(VERSION + 1)
+        );
+        // Objects of earlier versions lack the fields added since
+        invalid[4] = 1;
+        assert_eq!(
+            PublicKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::UnsupportedVersion(1)
+        );
+        assert_eq!(
+            RelinearizationKey1::from_bytes(&bytes).unwrap_err(),
+            SerializationError::UnexpectedType(PUBLIC_KEY)
+        );
+        assert_eq!(
+            PublicKey::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+            SerializationError::Truncated
+        );
+        assert_eq!(
+            PublicKey::from_bytes(&[bytes.clone(), vec![0]].concat()).unwrap_err(),
+            SerializationError::TrailingBytes
+        );
+
+        // A degree that is not a power of two
+        invalid = bytes.clone();
+        invalid[6] = 15;
+        assert_eq!(
+            PublicKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidParameters(ParametersError::DegreeNotPowerOfTwo(15))
+        );
+
+        // A coefficient of p_0 that is not below q = 65537 (17 bits)
+        invalid = bytes;
+        let header_len = Writer::with_params(PUBLIC_KEY, &params).bytes.len() + 8;
+        invalid[header_len] = 0xff;
+        invalid[header_len + 1] = 0xff;
+        invalid[header_len + 2] |= 0x01;
+        assert_eq!(
+            PublicKey::from_bytes(&invalid).unwrap_err(),
+            SerializationError::InvalidValue
+        );
+    }
+}
@@ -1,18 +1,28 @@
 #[cfg(test)]
 mod tests {
-    use crate::keys::SecretKey;
+    use crate::big_poly::BigPoly;
+    use crate::ciphertext::{Ciphertext, CompressedCiphertext};
+    use crate::error::Error;
+    use crate::keys::{PublicKey, RelinearizationKey1, RelinearizationKey2, SecretKey};
+    use crate::params::{Parameters, ParametersError, RnsParameters};
     use crate::plaintext::Plaintext;
+    use crate::poly::Poly;
+    use crate::random_source::{ErrorDistribution, SecretDistribution};
+    use crate::rns::RnsBasis;
+    use crate::security::SecurityPolicy;
+    use num_bigint::BigInt;
     use rand::SeedableRng;
 
     fn encrypt_decrypt_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
         let degree = msg.len();
         let mut rng = rand::rngs::StdRng::seed_from_u64(18);
 
-        let secret_key = SecretKey::generate(degree, &mut rng);
-        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let params = Parameters::new(degree, q, t, std_dev).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
 
         let plaintext = Plaintext::new(msg, t);
-        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext = plaintext.encrypt(&public_key, &mut rng);
 
         let decrypted = ciphertext.decrypt(&secret_key);
 
@@ -21,7 +31,7 @@ mod tests {
 
     #[test]
     fn encrypt_decrypt() {
-        for t in vec![2, 4, 8, 16, 32].iter() {
+        for t in [2, 4, 8, 16, 32].iter() {
             encrypt_decrypt_helper(vec![1, 0], *t, 65536, 3.2);
             encrypt_decrypt_helper(vec![3, 2, 1, 0], *t, 65536, 3.2);
             encrypt_decrypt_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, 65536, 3.2);
@@ -38,16 +48,17 @@ mod tests {
         let degree = msg_1.len();
         let mut rng = rand::rngs::StdRng::seed_from_u64(19);
 
-        let secret_key = SecretKey::generate(degree, &mut rng);
-        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let params = Parameters::new(degree, q, t, std_dev).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
 
         let plaintext_1 = Plaintext::new(msg_1, t);
-        let ciphertext_1 = plaintext_1.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_1 = plaintext_1.encrypt(&public_key, &mut rng);
         let decrypted_1 = ciphertext_1.decrypt(&secret_key);
         assert_eq!(decrypted_1.poly(), plaintext_1.poly() % (t, degree));
 
         let plaintext_2 = Plaintext::new(msg_2, t);
-        let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_2 = plaintext_2.encrypt(&public_key, &mut rng);
         let decrypted_2 = ciphertext_2.decrypt(&secret_key);
         assert_eq!(decrypted_2.poly(), plaintext_2.poly() % (t, degree));
 
@@ -69,7 +80,7 @@ mod tests {
 
     #[test]
     fn encrypt_add_sub_decrypt() {
-        for t in vec![2, 4, 8, 16, 32].iter() {
+        for t in [2, 4, 8, 16, 32].iter() {
             encrypt_add_sub_decrypt_helper(vec![0, 6], vec![7, 2], *t, 65536, 3.2);
             encrypt_add_sub_decrypt_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, 65536, 3.2);
             encrypt_add_sub_decrypt_helper(
@@ -86,22 +97,23 @@ mod tests {
         let degree = msg_1.len();
         let mut rng = rand::rngs::StdRng::seed_from_u64(20);
 
-        let secret_key = SecretKey::generate(degree, &mut rng);
-        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let params = Parameters::new(degree, q, t, std_dev).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
 
         let plaintext_1 = Plaintext::new(msg_1, t);
-        let ciphertext_1 = plaintext_1.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_1 = plaintext_1.encrypt(&public_key, &mut rng);
         let plaintext_2 = Plaintext::new(msg_2, t);
-        let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_2 = plaintext_2.encrypt(&public_key, &mut rng);
 
         // Multiply without relinearizing
         let (c_0, c_1, c_2) = ciphertext_1.clone().basic_mul(ciphertext_2.clone());
 
         // Decrypt non-relinearized multilication output
         let s = secret_key.poly;
-        let delta_inv = t as f64 / q as f64;
-        let raw = c_0.clone() + c_1.clone() * s.clone() + c_2.clone() * s.clone() * s.clone();
-        let decrypted_mul = (raw * delta_inv) % (t, degree);
+        let s_2 = params.mul_mod(&s, &s);
+        let raw = (c_0 + params.mul_mod(&c_1, &s) + params.mul_mod(&c_2, &s_2)) % (q, degree);
+        let decrypted_mul = raw.scale_round(t, q) % (t, degree);
 
         assert_eq!(
             decrypted_mul,
@@ -109,15 +121,62 @@ mod tests {
         );
     }
 
-    // Test that ciphertext multiplication without relinearization encrypt/decrypts correctly
+    // Test that ciphertext multiplication without relinearization encrypt/decrypts correctly.
+    // The noise after a multiplication grows with t, so for t up to 32 the multiplication tests
+    // need a larger q than 2^16 to leave room for the (centered, discrete Gaussian) error.
     #[test]
     fn basic_mul_test() {
-        for t in vec![2, 4, 8, 16, 32].iter() {
-            basic_mul_helper(vec![0, 6], vec![7, 2], *t, 65536, 1.0);
-            basic_mul_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, 65536, 1.0);
+        for t in [2, 4, 8, 16, 32].iter() {
+            basic_mul_helper(vec![0, 6], vec![7, 2], *t, 1 << 20, 1.0);
+            basic_mul_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, 1 << 20, 1.0);
         }
     }
 
+    // Test basic multiplication against an exact big integer reference, with a modulus large enough
+    // that the tensor products overflow an i64.
+    #[test]
+    fn basic_mul_exact_reference_test() {
+        let degree = 16;
+        let q = crate::ntt::find_ntt_prime(40, degree).unwrap();
+        let t = 256;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(20);
+
+        let params = Parameters::new(degree, q, t, std_dev).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        let plaintext_1 = Plaintext::rand(&params, &mut rng);
+        let plaintext_2 = Plaintext::rand(&params, &mut rng);
+        let ciphertext_1 = plaintext_1.encrypt(&public_key, &mut rng);
+        let ciphertext_2 = plaintext_2.encrypt(&public_key, &mut rng);
+
+        let (c_0, c_1, c_2) = ciphertext_1.basic_mul(ciphertext_2.clone());
+
+        // Reference: schoolbook multiplication over the big integers, and exact scaling by t/q.
+        let (t_big, q_big) = (BigInt::from(t), BigInt::from(q));
+        let big = |poly: &crate::poly::Poly| BigPoly::from(poly);
+        let scale = |x: BigPoly| x.fold(degree).scale_round(&t_big, &q_big) % (&q_big, degree);
+        let expected_0 = scale(big(&ciphertext_1.c_0) * big(&ciphertext_2.c_0));
+        let expected_1 = scale(
+            big(&ciphertext_1.c_0) * big(&ciphertext_2.c_1)
+                + big(&ciphertext_1.c_1) * big(&ciphertext_2.c_0),
+        );
+        let expected_2 = scale(big(&ciphertext_1.c_1) * big(&ciphertext_2.c_1));
+        assert_eq!(big(&c_0), expected_0);
+        assert_eq!(big(&c_1), expected_1);
+        assert_eq!(big(&c_2), expected_2);
+
+        // Decrypt the non-relinearized output exactly.
+        let s = big(&secret_key.poly);
+        let raw =
+            (big(&c_0) + big(&c_1) * s.clone() + big(&c_2) * s.clone() * s) % (&q_big, degree);
+        let decrypted_mul = raw.scale_round(&t_big, &q_big) % (&t_big, degree);
+        assert_eq!(
+            decrypted_mul.to_poly(),
+            (plaintext_1.poly() * plaintext_2.poly()) % (t, degree)
+        );
+    }
+
     fn relin_1_mul_helper(
         msg_1: Vec<i64>,
         msg_2: Vec<i64>,
@@ -129,16 +188,19 @@ mod tests {
         let degree = msg_1.len();
         let mut rng = rand::rngs::StdRng::seed_from_u64(21);
 
-        let secret_key = SecretKey::generate(degree, &mut rng);
-        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let params = Parameters::new(degree, q, t, std_dev)
+            .and_then(|params| params.with_rlk_base(base))
+            .unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
 
         let plaintext_1 = Plaintext::new(msg_1, t);
-        let ciphertext_1 = plaintext_1.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_1 = plaintext_1.encrypt(&public_key, &mut rng);
         let plaintext_2 = Plaintext::new(msg_2, t);
-        let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_2 = plaintext_2.encrypt(&public_key, &mut rng);
 
         // Homomorphic multiplication with relinearization
-        let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, base);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
         let mul_ciphertext = ciphertext_1.clone() * (ciphertext_2.clone(), &rlk_1);
         let decrypted_mul = mul_ciphertext.decrypt(&secret_key);
         assert_eq!(
@@ -150,14 +212,14 @@ mod tests {
     // Test that ciphertext multiplication using relinearization Version #1 encrypt/decrypts correctly
     #[test]
     fn relin_1_mul_test() {
-        let q = 65536;
+        let q = 1 << 20;
         // Choosing T = ceil(sqrt(q)) to minimize relinearisation time and space.
         // This can be toggled to be smaller so that the error introduced is smaller.
-        // With this base choice, we can tolerate error to std_dev=1.5.
+        // With this base choice, we use a smaller std_dev to leave room for the error.
         let base_sqrt = (q as f64).sqrt().ceil() as i64;
         let std_dev_sqrt = 1.5;
 
-        for t in vec![4, 8, 16, 32].iter() {
+        for t in [4, 8, 16, 32].iter() {
             relin_1_mul_helper(vec![0, 1], vec![0, 0], *t, q, std_dev_sqrt, base_sqrt);
             relin_1_mul_helper(
                 vec![3, 2, 1, 0],
@@ -178,11 +240,11 @@ mod tests {
         }
 
         // Choosing T = log_2(q) to decrease error at the cost of relinearisation time and space.
-        // With this base choice, we can tolerate error to std_dev=2.9.
+        // With this base choice, we can tolerate a larger std_dev.
         let base_log = (q as f64).log2() as i64;
         let std_dev_log = 2.9;
 
-        for t in vec![4, 8, 16, 32].iter() {
+        for t in [4, 8, 16, 32].iter() {
             relin_1_mul_helper(vec![0, 1], vec![0, 0], *t, q, std_dev_log, base_log);
             relin_1_mul_helper(
                 vec![3, 2, 1, 0],
@@ -207,16 +269,17 @@ mod tests {
         let degree = msg_1.len();
         let mut rng = rand::rngs::StdRng::seed_from_u64(22);
 
-        let secret_key = SecretKey::generate(degree, &mut rng);
-        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let params = Parameters::new(degree, q, t, std_dev).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
 
         let plaintext_1 = Plaintext::new(msg_1, t);
-        let ciphertext_1 = plaintext_1.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_1 = plaintext_1.encrypt(&public_key, &mut rng);
         let plaintext_2 = Plaintext::new(msg_2, t);
-        let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_2 = plaintext_2.encrypt(&public_key, &mut rng);
 
         // Homomorphic multiplication with relinearization
-        let rlk_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p);
+        let rlk_2 = secret_key.relin_key_gen_2(&params, p, &mut rng);
         let mul_ciphertext = ciphertext_1.clone() * (ciphertext_2.clone(), &rlk_2);
         let decrypted_mul = mul_ciphertext.decrypt(&secret_key);
         assert_eq!(
@@ -228,13 +291,13 @@ mod tests {
     // Test that ciphertext multiplication using relinearization Version #2 encrypt/decrypts correctly
     #[test]
     fn relin_2_mul_test() {
-        let q = 65536;
-        // Technically p should be >= q^3 for security (see paper discussion on Relinearization Version 2),
-        // but setting p = q^3 results in an overflow when taking p * q so we will test with a smaller p.
-        let p = 2_i64.pow(13) * q;
+        let q = 1 << 20;
+        // p should be >= q^3 for security (see paper discussion on Relinearization Version 2).
+        // p * q doesn't fit in an i64, so this also checks that the key is computed with big integers.
+        let p = q * q * q;
         let std_dev = 2.0;
 
-        for t in vec![4, 8, 16, 32].iter() {
+        for t in [4, 8, 16, 32].iter() {
             relin_2_mul_helper(vec![0, 1], vec![0, 0], *t, q, std_dev, p);
             relin_2_mul_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, q, std_dev, p);
             relin_2_mul_helper(
@@ -255,22 +318,22 @@ mod tests {
             let t = 16;
             let std_dev = 3.2;
             let degree = 4;
-            let rlk_base = (q as f64).log2() as i64;
             let mut rng = rand::rngs::StdRng::seed_from_u64(23);
 
-            let secret_key = SecretKey::generate(degree, &mut rng);
-            let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
-            let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base);
+            let params = Parameters::new(degree, q, t, std_dev).unwrap();
+            let secret_key = SecretKey::generate(&params, &mut rng);
+            let public_key = secret_key.public_key_gen(&params, &mut rng);
+            let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
 
-            let pt_1 = Plaintext::rand(degree, t, &mut rng);
-            let pt_2 = Plaintext::rand(degree, t, &mut rng);
-            let pt_3 = Plaintext::rand(degree, t, &mut rng);
-            let pt_4 = Plaintext::rand(degree, t, &mut rng);
+            let pt_1 = Plaintext::rand(&params, &mut rng);
+            let pt_2 = Plaintext::rand(&params, &mut rng);
+            let pt_3 = Plaintext::rand(&params, &mut rng);
+            let pt_4 = Plaintext::rand(&params, &mut rng);
 
-            let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng);
-            let ct_2 = pt_2.encrypt(&public_key, std_dev, &mut rng);
-            let ct_3 = pt_3.encrypt(&public_key, std_dev, &mut rng);
-            let ct_4 = pt_4.encrypt(&public_key, std_dev, &mut rng);
+            let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+            let ct_2 = pt_2.encrypt(&public_key, &mut rng);
+            let ct_3 = pt_3.encrypt(&public_key, &mut rng);
+            let ct_4 = pt_4.encrypt(&public_key, &mut rng);
 
             let expr_ct = ct_1 * (ct_2, &rlk_1) + ct_3 * (ct_4, &rlk_1);
             let expr_pt = expr_ct.decrypt(&secret_key);
@@ -279,4 +342,714 @@ mod tests {
             assert_eq!(expr_pt.poly(), expected_pt);
         }
     }
+
+    // Test that encryption, decryption and multiplication work when q is NTT-friendly,
+    // so that multiplication in R_q uses the NTT instead of schoolbook multiplication.
+    #[test]
+    fn ntt_friendly_modulus_test() {
+        let q = 65537;
+        let base = (q as f64).log2() as i64;
+        for t in [2, 4, 8, 16].iter() {
+            encrypt_decrypt_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, q, 3.2);
+            encrypt_add_sub_decrypt_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, q, 3.2);
+            basic_mul_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, q, 1.0);
+            relin_1_mul_helper(
+                vec![0, 1, 2, 3, 0, 1, 2, 3],
+                vec![3, 2, 1, 0, 3, 2, 1, 0],
+                *t,
+                q,
+                2.0,
+                base,
+            );
+        }
+
+        // A larger degree and modulus, which would be slow with schoolbook multiplication.
+        let degree = 1024;
+        let q = crate::ntt::find_ntt_prime(40, degree).unwrap();
+        let msg: Vec<i64> = (0..degree as i64).map(|i| i % 16).collect();
+        encrypt_decrypt_helper(msg, 16, q, 3.2);
+    }
+
+    // Test that encryption, decryption and multiplication work with ternary and sparse ternary
+    // secret keys, and centered binomial errors.
+    #[test]
+    fn secret_and_error_distributions_test() {
+        let degree = 1024;
+        let t = 16;
+        let q = crate::ntt::find_ntt_prime(40, degree).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(24);
+
+        let secret_distributions = [
+            SecretDistribution::Ternary,
+            SecretDistribution::SparseTernary { hamming_weight: 64 },
+        ];
+        for secret_distribution in secret_distributions.iter() {
+            let params = Parameters::new(degree, q, t, 3.2)
+                .and_then(|params| {
+                    params.with_error_distribution(ErrorDistribution::CenteredBinomial { eta: 8 })
+                })
+                .and_then(|params| params.with_secret_distribution(*secret_distribution))
+                .unwrap();
+            let secret_key = SecretKey::generate(&params, &mut rng);
+            assert!(secret_key.poly.val().contains(&-1));
+            let public_key = secret_key.public_key_gen(&params, &mut rng);
+            let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+
+            let pt_1 = Plaintext::rand(&params, &mut rng);
+            let pt_2 = Plaintext::rand(&params, &mut rng);
+            let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+            let ct_2 = pt_2.encrypt(&public_key, &mut rng);
+
+            assert_eq!(ct_1.decrypt(&secret_key).poly(), pt_1.poly());
+            assert_eq!(
+                (ct_1.clone() - ct_2.clone()).decrypt(&secret_key).poly(),
+                (pt_1.poly() - pt_2.poly()) % (t, degree)
+            );
+            assert_eq!(
+                (ct_1 * (ct_2, &rlk_1)).decrypt(&secret_key).poly(),
+                (pt_1.poly() * pt_2.poly()) % (t, degree)
+            );
+        }
+    }
+
+    // Test that ciphertexts encrypted with the secret key decrypt correctly, have less noise than
+    // ciphertexts encrypted with the public key, and can be combined with them.
+    #[test]
+    fn secret_key_encryption_test() {
+        let degree = 1024;
+        let t = 16;
+        let q = crate::ntt::find_ntt_prime(40, degree).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(26);
+
+        let params = Parameters::new(degree, q, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+
+        let pt_1 = Plaintext::rand(&params, &mut rng);
+        let pt_2 = Plaintext::rand(&params, &mut rng);
+        let ct_1 = secret_key.encrypt(&pt_1, &params, &mut rng);
+        let ct_2 = pt_2.encrypt(&public_key, &mut rng);
+        assert_eq!(ct_1.decrypt(&secret_key), pt_1);
+
+        // The noise c_0 + c_1 * s - delta * m, lifted to (-q/2, q/2]
+        let noise = |ct: &crate::ciphertext::Ciphertext, pt: &Plaintext| -> i64 {
+            let raw = (ct.c_0.clone() + params.mul_mod(&ct.c_1, &secret_key.poly)
+                - pt.poly() * params.delta())
+                % (q, degree);
+            raw.val()
+                .iter()
+                .map(|c| if *c > q / 2 { q - c } else { *c })
+                .max()
+                .unwrap()
+        };
+        // The secret key error is a single Gaussian sample, within the tail cut of 10 sigma
+        assert!(noise(&ct_1, &pt_1) <= 32);
+        assert!(noise(&ct_1, &pt_1) < noise(&ct_2, &pt_2));
+
+        assert_eq!(
+            (ct_1.clone() + ct_2.clone()).decrypt(&secret_key).poly(),
+            (pt_1.poly() + pt_2.poly()) % (t, degree)
+        );
+        assert_eq!(
+            (ct_1 * (ct_2, &rlk_1)).decrypt(&secret_key).poly(),
+            (pt_1.poly() * pt_2.poly()) % (t, degree)
+        );
+    }
+
+    // Test that seeded keys, loaded from their serialized seeds, work like regular keys.
+    #[test]
+    fn seeded_keys_test() {
+        let (degree, t, q) = (8, 16, 1 << 40);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(27);
+
+        let params = Parameters::new(degree, q, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen_seeded(&params, &mut rng);
+        let public_key = PublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        let rlk_1 = secret_key.relin_key_gen_1_seeded(&params, &mut rng);
+        let rlk_1 = RelinearizationKey1::from_bytes(&rlk_1.to_bytes()).unwrap();
+        // p = 8q is too small for security, but enough for correctness
+        let rlk_2 = secret_key.relin_key_gen_2_seeded(&params, q * 8, &mut rng);
+        let rlk_2 = RelinearizationKey2::from_bytes(&rlk_2.to_bytes()).unwrap();
+
+        let pt_1 = Plaintext::rand(&params, &mut rng);
+        let pt_2 = Plaintext::rand(&params, &mut rng);
+        let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+        let ct_2 = pt_2.encrypt(&public_key, &mut rng);
+        let expected = (pt_1.poly() * pt_2.poly()) % (t, degree);
+        assert_eq!(
+            (ct_1.clone() * (ct_2.clone(), &rlk_1))
+                .decrypt(&secret_key)
+                .poly(),
+            expected
+        );
+        assert_eq!(
+            (ct_1 * (ct_2, &rlk_2)).decrypt(&secret_key).poly(),
+            expected
+        );
+    }
+
+    fn rns_helper(msg_1: Vec<i64>, msg_2: Vec<i64>, t: i64, prime_count: usize, std_dev: f64) {
+        let degree = msg_1.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(24);
+        let basis = RnsBasis::generate(prime_count, 50, degree);
+        let params = RnsParameters::new(&basis, t, std_dev).unwrap();
+
+        let secret_key = SecretKey::generate_rns(&params, &mut rng);
+        let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+        let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+
+        let plaintext_1 = Plaintext::new(msg_1, t);
+        let ciphertext_1 = plaintext_1.encrypt_rns(&public_key, &mut rng);
+        let plaintext_2 = Plaintext::new(msg_2, t);
+        let ciphertext_2 = plaintext_2.encrypt_rns(&public_key, &mut rng);
+
+        let decrypted_1 = ciphertext_1.decrypt(&secret_key);
+        assert_eq!(decrypted_1.poly(), plaintext_1.poly() % (t, degree));
+
+        let decrypted_add = (ciphertext_1.clone() + ciphertext_2.clone()).decrypt(&secret_key);
+        let expected_add = (plaintext_1.poly() + plaintext_2.poly()) % (t, degree);
+        assert_eq!(decrypted_add.poly(), expected_add);
+
+        let decrypted_sub = (ciphertext_1.clone() - ciphertext_2.clone()).decrypt(&secret_key);
+        let expected_sub = (plaintext_1.poly() - plaintext_2.poly()) % (t, degree);
+        assert_eq!(decrypted_sub.poly(), expected_sub);
+
+        let decrypted_neg = (-ciphertext_1.clone()).decrypt(&secret_key);
+        assert_eq!(decrypted_neg.poly(), -plaintext_1.poly() % (t, degree));
+
+        // Square the product, to check that multiplication works at depth 2.
+        let mul_ciphertext = ciphertext_1 * (ciphertext_2, &relin_key);
+        let decrypted_mul = mul_ciphertext.clone().decrypt(&secret_key);
+        let expected_mul = (plaintext_1.poly() * plaintext_2.poly()) % (t, degree);
+        assert_eq!(decrypted_mul.poly(), expected_mul);
+
+        let square_ciphertext = mul_ciphertext.clone() * (mul_ciphertext, &relin_key);
+        let decrypted_square = square_ciphertext.decrypt(&secret_key);
+        assert_eq!(
+            decrypted_square.poly(),
+            (expected_mul.clone() * expected_mul) % (t, degree)
+        );
+    }
+
+    // Test that encryption, decryption, addition and multiplication work for a ciphertext modulus
+    // of hundreds of bits, represented by an RNS basis.
+    #[test]
+    fn rns_test() {
+        for t in [2, 16, 256, 65537].iter() {
+            rns_helper(vec![0, 1], vec![1, 1], *t, 6, 3.2);
+            rns_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, 6, 3.2);
+            rns_helper(
+                vec![0, 1, 2, 3, 4, 5, 6, 7],
+                vec![7, 6, 5, 4, 3, 2, 1, 0],
+                *t,
+                6,
+                3.2,
+            );
+        }
+
+        let degree = 1024;
+        let msg_1: Vec<i64> = (0..degree as i64).map(|i| i % 7).collect();
+        let msg_2: Vec<i64> = (0..degree as i64).map(|i| (i * 5) % 11).collect();
+        rns_helper(msg_1, msg_2, 1024, 4, 3.2);
+    }
+
+    // Test that the RNS secret key follows the secret distribution of the parameters, and that
+    // encryption and multiplication work with it.
+    #[test]
+    fn rns_secret_distribution_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(41);
+        let (degree, t) = (1024, 256);
+        let basis = RnsBasis::generate(4, 50, degree);
+        let params = RnsParameters::new(&basis, t, 3.2)
+            .unwrap()
+            .with_secret_distribution(SecretDistribution::SparseTernary { hamming_weight: 64 })
+            .unwrap();
+
+        let secret_key = SecretKey::generate_rns(&params, &mut rng);
+        let s = secret_key.poly.val();
+        assert_eq!(s.iter().filter(|s_i| **s_i != 0).count(), 64);
+        assert!(s.contains(&-1));
+        let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+        let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+
+        let pt_1 = Plaintext::new((0..degree as i64).map(|i| (i * 3) % t).collect(), t);
+        let pt_2 = Plaintext::new((0..degree as i64).map(|i| (i * 7 + 1) % t).collect(), t);
+        let ct_1 = pt_1.encrypt_rns(&public_key, &mut rng);
+        let ct_2 = pt_2.encrypt_rns(&public_key, &mut rng);
+
+        assert_eq!(ct_1.decrypt(&secret_key), pt_1);
+        assert_eq!(
+            (ct_1 * (ct_2, &relin_key)).decrypt(&secret_key).poly(),
+            (pt_1.poly() * pt_2.poly()) % (t, degree)
+        );
+    }
+
+    #[test]
+    fn rns_key_mismatch_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let basis = RnsBasis::generate(3, 50, 16);
+        let params = RnsParameters::new(&basis, 12, 3.2).unwrap();
+
+        let secret_key = SecretKey::generate_rns(&params, &mut rng);
+        let other_secret_key = SecretKey::generate_rns(&params, &mut rng);
+        let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+        let other_public_key = other_secret_key.rns_public_key_gen(&params, &mut rng);
+        let other_relin_key = other_secret_key.rns_relin_key_gen(&params, &mut rng);
+        assert_eq!(public_key.id(), secret_key.id());
+
+        let pt = Plaintext::new(vec![1; 16], 12);
+        let ct = pt.encrypt_rns(&public_key, &mut rng);
+        let other_ct = pt.encrypt_rns(&other_public_key, &mut rng);
+        assert_eq!(ct.key_id(), secret_key.id());
+
+        assert_eq!(
+            ct.try_decrypt(&other_secret_key).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_add(other_ct.clone()).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_sub(other_ct).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul(ct, &other_relin_key).unwrap_err(),
+            Error::KeyMismatch
+        );
+    }
+
+    #[test]
+    fn rns_noise_budget_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(44);
+        let (degree, t) = (1024, 16);
+
+        // With a single prime, the noise is the same as for the equivalent ciphertext modulo q.
+        let q = crate::ntt::find_ntt_prime(50, degree).unwrap();
+        let params = Parameters::new(degree, q, t, 3.2).unwrap();
+        let rns_params = RnsParameters::new(&RnsBasis::new(vec![q], degree), t, 3.2).unwrap();
+        let secret_key = SecretKey::generate_rns(&rns_params, &mut rng);
+        let public_key = secret_key.rns_public_key_gen(&rns_params, &mut rng);
+        let rns_ct = Plaintext::rand(&params, &mut rng).encrypt_rns(&public_key, &mut rng);
+        let ct = Ciphertext {
+            c_0: Poly::new(rns_ct.c_0.residues()[0].clone()),
+            c_1: Poly::new(rns_ct.c_1.residues()[0].clone()),
+            params,
+            key_id: rns_ct.key_id(),
+            noise_bound: 0.0,
+        };
+        assert_eq!(
+            rns_ct.invariant_noise(&secret_key),
+            ct.invariant_noise(&secret_key)
+        );
+        assert_eq!(
+            rns_ct.noise_budget(&secret_key),
+            ct.noise_budget(&secret_key)
+        );
+
+        // With a 100-bit modulus, the budget is about 50 bits more than with a 50-bit one.
+        let basis = RnsBasis::generate(2, 50, degree);
+        let params = RnsParameters::new(&basis, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate_rns(&params, &mut rng);
+        let public_key = secret_key.rns_public_key_gen(&params, &mut rng);
+        let relin_key = secret_key.rns_relin_key_gen(&params, &mut rng);
+        let pt = Plaintext::new((0..degree as i64).map(|i| i % t).collect(), t);
+        let ct = pt.encrypt_rns(&public_key, &mut rng);
+
+        let fresh = ct.noise_budget(&secret_key);
+        assert!((80..95).contains(&fresh));
+        let v = ct.invariant_noise(&secret_key);
+        assert!(v > 0.0 && v < 0.5);
+        assert_eq!(fresh, (-(2.0 * v).log2()).floor() as u32);
+
+        // Decryption stays correct until the budget runs out
+        let mut ct = ct;
+        let mut expected = pt.poly() % (t, degree);
+        let mut budget = fresh;
+        while budget > 0 {
+            assert_eq!(ct.decrypt(&secret_key).poly(), expected);
+            ct = ct.clone() * (ct, &relin_key);
+            expected = (expected.clone() * expected) % (t, degree);
+            let next = ct.noise_budget(&secret_key);
+            assert!(next + 14 <= budget || next == 0);
+            budget = next;
+        }
+    }
+
+    // Test that encryption, decryption and multiplication are exact for a modulus close to 2^62,
+    // where coefficients don't fit in an f64 mantissa.
+    #[test]
+    fn large_modulus_test() {
+        let q = crate::ntt::find_ntt_prime(61, 8).unwrap();
+        for t in [2, 1 << 10, 1 << 20].iter() {
+            encrypt_decrypt_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, q, 3.2);
+            encrypt_add_sub_decrypt_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, q, 3.2);
+            basic_mul_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, q, 3.2);
+            // A small base gives many levels, whose sum must not overflow during relinearization.
+            for base in [1 << 16, 61].iter() {
+                relin_1_mul_helper(
+                    vec![0, 1, 2, 3, 0, 1, 2, 3],
+                    vec![3, 2, 1, 0, 3, 2, 1, 0],
+                    *t,
+                    q,
+                    3.2,
+                    *base,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn compressed_ciphertext_test() {
+        let degree = 1024;
+        let t = 16;
+        let q = crate::ntt::find_ntt_prime(40, degree).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(31);
+
+        let params = Parameters::new(degree, q, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+
+        let pt_1 = Plaintext::rand(&params, &mut rng);
+        let pt_2 = Plaintext::rand(&params, &mut rng);
+        let compressed = secret_key.encrypt_compressed(&pt_1, &params, &mut rng);
+        let bytes = compressed.to_bytes();
+        let ct_1 = CompressedCiphertext::from_bytes(&bytes).unwrap().expand();
+        let ct_2 = secret_key.encrypt(&pt_2, &params, &mut rng);
+
+        assert_eq!(ct_1.c_1, compressed.expand().c_1);
+        assert_eq!(ct_1.decrypt(&secret_key), pt_1);
+        assert_eq!(
+            (ct_1 * (ct_2, &rlk_1)).decrypt(&secret_key).poly(),
+            (pt_1.poly() * pt_2.poly()) % (t, degree)
+        );
+    }
+
+    #[test]
+    fn error_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(35);
+        let params = Parameters::new(16, 65536, 12, 3.2).unwrap();
+        let other_params = Parameters::new(16, 65536, 16, 3.2).unwrap();
+        let large_params = Parameters::new(32, 65536, 12, 3.2).unwrap();
+
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let large_secret_key = SecretKey::generate(&large_params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+        let other_rlk_1 = secret_key.relin_key_gen_1(&other_params, &mut rng);
+        let other_rlk_2 = secret_key.relin_key_gen_2(&other_params, 1 << 40, &mut rng);
+
+        let pt = Plaintext::rand(&params, &mut rng);
+        let ct = secret_key.try_encrypt(&pt, &params, &mut rng).unwrap();
+        let other_pt = Plaintext::rand(&other_params, &mut rng);
+        let other_ct = secret_key.encrypt(&other_pt, &other_params, &mut rng);
+
+        assert_eq!(
+            ct.clone().try_add(other_ct.clone()).unwrap_err(),
+            Error::ParametersMismatch
+        );
+        assert_eq!(
+            ct.clone().try_sub(other_ct.clone()).unwrap_err(),
+            Error::ParametersMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul_1(other_ct, &rlk_1).unwrap_err(),
+            Error::ParametersMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul_1(ct.clone(), &other_rlk_1).unwrap_err(),
+            Error::ParametersMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul_2(ct.clone(), &other_rlk_2).unwrap_err(),
+            Error::ParametersMismatch
+        );
+        assert_eq!(
+            large_secret_key
+                .try_encrypt(&pt, &params, &mut rng)
+                .unwrap_err(),
+            Error::DegreeMismatch {
+                expected: 16,
+                found: 32
+            }
+        );
+        assert_eq!(
+            secret_key
+                .try_encrypt(&other_pt, &params, &mut rng)
+                .unwrap_err(),
+            Error::PlaintextModulusMismatch {
+                expected: 12,
+                found: 16
+            }
+        );
+        assert_eq!(
+            large_secret_key
+                .try_relin_key_gen_1(&params, &mut rng)
+                .unwrap_err(),
+            Error::DegreeMismatch {
+                expected: 16,
+                found: 32
+            }
+        );
+
+        let rejecting = params
+            .clone()
+            .with_security_policy(SecurityPolicy::Reject { min_bits: 128 });
+        assert!(matches!(
+            secret_key.try_public_key_gen(&rejecting, &mut rng),
+            Err(Error::InvalidParameters(ParametersError::Insecure { .. }))
+        ));
+        assert!(matches!(
+            secret_key.try_relin_key_gen_2(&rejecting, 1 << 40, &mut rng),
+            Err(Error::InvalidParameters(ParametersError::Insecure { .. }))
+        ));
+    }
+
+    #[test]
+    fn key_mismatch_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(36);
+        let params = Parameters::new(16, 65536, 12, 3.2).unwrap();
+
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let other_secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+        let other_rlk_1 = other_secret_key.relin_key_gen_1(&params, &mut rng);
+        let other_rlk_2 = other_secret_key.relin_key_gen_2(&params, 1 << 40, &mut rng);
+        assert_ne!(secret_key.id(), other_secret_key.id());
+        assert_eq!(public_key.id(), secret_key.id());
+
+        // Ciphertexts under the same key match, whether they were encrypted with the public key,
+        // the secret key, or loaded from bytes
+        let pt = Plaintext::rand(&params, &mut rng);
+        let ct = pt.encrypt(&public_key, &mut rng);
+        let loaded = SecretKey::from_bytes(&secret_key.to_bytes()).unwrap();
+        let ct_sk = loaded.encrypt(&pt, &params, &mut rng);
+        assert_eq!(ct.key_id(), ct_sk.key_id());
+        assert!(ct.clone().try_mul_1(ct_sk, &rlk_1).is_ok());
+
+        let other_ct = other_secret_key.encrypt(&pt, &params, &mut rng);
+        assert_eq!(
+            ct.clone().try_add(other_ct.clone()).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_sub(other_ct.clone()).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul_1(other_ct, &rlk_1).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul_1(ct.clone(), &other_rlk_1).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.clone().try_mul_2(ct.clone(), &other_rlk_2).unwrap_err(),
+            Error::KeyMismatch
+        );
+        assert_eq!(
+            ct.try_decrypt(&other_secret_key).unwrap_err(),
+            Error::KeyMismatch
+        );
+    }
+
+    #[test]
+    fn noise_budget_test() {
+        let degree = 1024;
+        let t = 16;
+        let q = crate::ntt::find_ntt_prime(50, degree).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(37);
+
+        let params = Parameters::new(degree, q, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+        let rlk_2 = secret_key.relin_key_gen_2(&params, 1 << 61, &mut rng);
+
+        let pt_1 = Plaintext::rand(&params, &mut rng);
+        let pt_2 = Plaintext::rand(&params, &mut rng);
+        let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+        let ct_2 = pt_2.encrypt(&public_key, &mut rng);
+        let ct_sk = secret_key.encrypt(&pt_1, &params, &mut rng);
+
+        // A fresh ciphertext's noise is within a few bits of t * N * B / q for error bound B,
+        // and the secret key encryption has less noise
+        let fresh = ct_1.noise_budget(&secret_key);
+        assert!((30..45).contains(&fresh));
+        assert!(ct_sk.noise_budget(&secret_key) > fresh);
+        let v = ct_1.invariant_noise(&secret_key);
+        assert!(v > 0.0 && v < 0.5);
+        assert_eq!(fresh, (-(2.0 * v).log2()).floor() as u32);
+
+        // Addition uses at most a bit
+        let sum = ct_1.clone() + ct_2.clone();
+        assert!(sum.noise_budget(&secret_key) + 1 >= fresh);
+
+        // Multiplication uses roughly log2(t * N) bits, or more
+        let budget = |ct: Ciphertext| ct.noise_budget(&secret_key);
+        let product_1 = ct_1.clone() * (ct_2.clone(), &rlk_1);
+        let product_2 = ct_1 * (ct_2, &rlk_2);
+        assert!(budget(product_1.clone()) + 14 <= fresh);
+        assert!(budget(product_2.clone()) + 14 <= fresh);
+
+        // Decryption stays correct until the budget runs out
+        let mut ct = product_1;
+        let mut expected = (pt_1.poly() * pt_2.poly()) % (t, degree);
+        while ct.noise_budget(&secret_key) > 0 {
+            assert_eq!(ct.decrypt(&secret_key).poly(), expected);
+            ct = ct.clone() * (ct, &rlk_1);
+            expected = (expected.clone() * expected) % (t, degree);
+        }
+    }
+
+    #[test]
+    fn noise_estimate_test() {
+        let degree = 1024;
+        let t = 16;
+        let q = crate::ntt::find_ntt_prime(50, degree).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(38);
+
+        let params = Parameters::new(degree, q, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+        let rlk_2 = secret_key.relin_key_gen_2(&params, 1 << 61, &mut rng);
+
+        let pt_1 = Plaintext::rand(&params, &mut rng);
+        let pt_2 = Plaintext::rand(&params, &mut rng);
+        let ct_1 = pt_1.encrypt(&public_key, &mut rng);
+        let ct_2 = pt_2.encrypt(&public_key, &mut rng);
+        let ct_sk = secret_key.encrypt(&pt_2, &params, &mut rng);
+
+        // The estimate bounds the measured noise through every operation
+        let check = |ct: &Ciphertext| {
+            assert!(ct.noise_bound() >= ct.invariant_noise(&secret_key));
+            assert!(ct.estimated_noise_budget() <= ct.noise_budget(&secret_key));
+        };
+        check(&ct_1);
+        check(&ct_sk);
+        assert!(ct_sk.noise_bound() < ct_1.noise_bound());
+        check(&(ct_1.clone() + ct_2.clone()));
+        check(&(ct_1.clone() - ct_sk.clone()));
+        check(&-ct_1.clone());
+        check(&(ct_1.clone() * &pt_2));
+
+        let bound_1 = ct_1.mul_1_noise_bound(&ct_2, &rlk_1);
+        let product_1 = ct_1.clone() * (ct_2.clone(), &rlk_1);
+        assert_eq!(product_1.noise_bound(), bound_1);
+        check(&product_1);
+        let bound_2 = ct_1.mul_2_noise_bound(&ct_sk, &rlk_2);
+        let product_2 = ct_1 * (ct_sk, &rlk_2);
+        assert_eq!(product_2.noise_bound(), bound_2);
+        check(&product_2);
+        check(&(product_1.clone() * &pt_1));
+
+        // A serialized ciphertext keeps its bound
+        let loaded = Ciphertext::from_bytes(&product_1.to_bytes()).unwrap();
+        assert_eq!(loaded.noise_bound(), product_1.noise_bound());
+
+        // One multiplication is guaranteed to be safe with these parameters, but not two
+        assert!(bound_1 < 0.5 && bound_2 < 0.5);
+        assert!(product_1.mul_1_noise_bound(&product_1, &rlk_1) >= 0.5);
+    }
+
+    #[test]
+    fn decrypt_checked_test() {
+        let degree = 1024;
+        let t = 16;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(39);
+
+        let params = Parameters::new(degree, 1 << 40, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+        let rlk_1 = secret_key.relin_key_gen_1(&params, &mut rng);
+        let other_key = SecretKey::generate(&params, &mut rng);
+
+        let pt = Plaintext::rand(&params, &mut rng);
+        let mut ct = pt.encrypt(&public_key, &mut rng);
+        assert_eq!(
+            ct.decrypt_checked(&other_key).unwrap_err(),
+            Error::KeyMismatch
+        );
+
+        // Checked decryption agrees with decryption until the noise overflows, and every wrong
+        // decryption is reported
+        let mut expected = pt.poly();
+        loop {
+            match ct.decrypt_checked(&secret_key) {
+                Ok(decrypted) => {
+                    assert_eq!(decrypted, ct.decrypt(&secret_key));
+                    assert_eq!(decrypted.poly(), expected);
+                }
+                Err(Error::NoiseOverflow { noise }) => {
+                    assert_eq!(noise, ct.invariant_noise(&secret_key));
+                    assert_eq!(ct.noise_budget(&secret_key), 0);
+                    break;
+                }
+                Err(err) => panic!("{}", err),
+            }
+            ct = ct.clone() * (ct, &rlk_1);
+            expected = (expected.clone() * expected) % (t, degree);
+        }
+        // The plaintext is lost once more multiplications follow
+        ct = ct.clone() * (ct, &rlk_1);
+        assert!(ct.decrypt_checked(&secret_key).is_err());
+    }
+
+    #[test]
+    fn plaintext_add_sub_test() {
+        let degree = 1024;
+        let t = 16;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(40);
+
+        let params = Parameters::new(degree, 1 << 40, t, 3.2).unwrap();
+        let secret_key = SecretKey::generate(&params, &mut rng);
+        let public_key = secret_key.public_key_gen(&params, &mut rng);
+
+        let pt_1 = Plaintext::rand(&params, &mut rng);
+        let pt_2 = Plaintext::rand(&params, &mut rng);
+        let ct = pt_1.encrypt(&public_key, &mut rng);
+
+        let sum = ct.clone() + &pt_2;
+        let expected = (pt_1.poly() + pt_2.poly()) % (t, degree);
+        assert_eq!(sum.decrypt(&secret_key).poly(), expected);
+        let difference = ct.clone() - &pt_2;
+        let expected = (pt_1.poly() - pt_2.poly()) % (t, degree);
+        assert_eq!(difference.decrypt(&secret_key).poly(), expected);
+
+        // Unreduced coefficients are reduced mod t first
+        let unreduced = Plaintext::new(pt_2.poly().val().iter().map(|c| c - t).collect(), t);
+        let sum_unreduced = ct.clone() + &unreduced;
+        assert_eq!(
+            (&sum_unreduced.c_0, &sum_unreduced.c_1),
+            (&sum.c_0, &sum.c_1)
+        );
+
+        // Adding a plaintext costs far less noise than adding its encryption
+        let encrypted_sum = ct.clone() + pt_2.encrypt(&public_key, &mut rng);
+        assert!(sum.invariant_noise(&secret_key) < encrypted_sum.invariant_noise(&secret_key));
+        assert!(sum.noise_bound() < encrypted_sum.noise_bound());
+        assert!(sum.noise_bound() >= sum.invariant_noise(&secret_key));
+
+        let other_t = Plaintext::new(vec![0; degree], 8);
+        assert_eq!(
+            ct.clone().try_add_plain(&other_t).unwrap_err(),
+            Error::PlaintextModulusMismatch {
+                expected: 16,
+                found: 8
+            }
+        );
+        let other_degree = Plaintext::new(vec![0; 16], t);
+        assert_eq!(
+            ct.try_sub_plain(&other_degree).unwrap_err(),
+            Error::DegreeMismatch {
+                expected: degree,
+                found: 16
+            }
+        );
+    }
 }